@@ -0,0 +1,93 @@
+use num::BigInt;
+
+/// The answer to one part of a puzzle, displayed exactly as it should be submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Answer {
+    Integer(u64),
+    Signed(i64),
+    BigInteger(BigInt),
+    Text(String),
+    Unsolved,
+}
+
+impl Answer {
+    pub(crate) fn is_solved(&self) -> bool {
+        *self != Answer::Unsolved
+    }
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{n}"),
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::BigInteger(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Integer(n as u64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::Integer(n)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Self {
+        Answer::Signed(n as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Signed(n)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(n: BigInt) -> Self {
+        Answer::BigInteger(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::Answer;
+    use num::BigInt;
+
+    #[test]
+    fn test_display_matches_submission_text() {
+        assert_eq!(
+            Answer::from(15746133679061usize).to_string(),
+            "15746133679061"
+        );
+        assert_eq!(Answer::from(-3isize).to_string(), "-3");
+        assert_eq!(
+            Answer::from(BigInt::from(u64::MAX) * 4).to_string(),
+            "73786976294838206460"
+        );
+        assert_eq!(Answer::from("EFJKLRZ").to_string(), "EFJKLRZ");
+        assert!(!Answer::Unsolved.is_solved());
+        assert!(Answer::from(0usize).is_solved());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::answer::Answer;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Accepted answers for one year, stored as `answers/<year>.toml`:
+///
+/// ```toml
+/// [day05]
+/// part1 = "26273516"
+/// part2 = "34039469"
+/// ```
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct AnswerStore {
+    days: BTreeMap<String, DayAnswers>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+pub(crate) const DEFAULT_ANSWER_DIR: &str = "answers";
+
+pub(crate) fn store_path(dir: &Path, year: u16) -> PathBuf {
+    dir.join(format!("{year}.toml"))
+}
+
+impl AnswerStore {
+    /// Load the store for `year`, or an empty one if nothing has been recorded yet.
+    pub(crate) fn load(dir: &Path, year: u16) -> std::io::Result<Self> {
+        match std::fs::read_to_string(store_path(dir, year)) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) fn save(&self, dir: &Path, year: u16) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let content = toml::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(store_path(dir, year), content)
+    }
+
+    pub(crate) fn get(&self, day: usize, part: usize) -> Option<&str> {
+        let answers = self.days.get(&day_key(day))?;
+        match part {
+            1 => answers.part1.as_deref(),
+            2 => answers.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn record(&mut self, day: usize, part: usize, answer: &str) {
+        let answers = self.days.entry(day_key(day)).or_default();
+        match part {
+            1 => answers.part1 = Some(answer.to_owned()),
+            2 => answers.part2 = Some(answer.to_owned()),
+            _ => panic!("no such part {part}"),
+        }
+    }
+
+    pub(crate) fn check(&self, day: usize, part: usize, answer: &Answer) -> Check {
+        match self.get(day, part) {
+            None => Check::Unrecorded,
+            Some(expected) if expected == answer.to_string() => Check::Match,
+            Some(expected) => Check::Mismatch {
+                expected: expected.to_owned(),
+            },
+        }
+    }
+
+    pub(crate) fn has_day(&self, day: usize) -> bool {
+        self.days.contains_key(&day_key(day))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Check {
+    Match,
+    Mismatch { expected: String },
+    Unrecorded,
+}
+
+fn day_key(day: usize) -> String {
+    format!("day{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answer::Answer,
+        answer_store::{AnswerStore, Check},
+    };
+    use std::path::Path;
+
+    #[test]
+    fn test_record_and_round_trip() {
+        let mut store = AnswerStore::default();
+        store.record(5, 1, "26273516");
+        store.record(14, 2, "105008");
+        store.record(14, 1, "102497");
+
+        assert_eq!(store.get(5, 1), Some("26273516"));
+        assert_eq!(store.get(5, 2), None);
+        assert_eq!(
+            toml::to_string(&store).unwrap(),
+            "[day05]\npart1 = \"26273516\"\n\n[day14]\npart1 = \"102497\"\npart2 = \"105008\"\n"
+        );
+
+        let dir = std::env::temp_dir().join("aoc-answer-store-test");
+        store.save(&dir, 2023).unwrap();
+        assert_eq!(AnswerStore::load(&dir, 2023).unwrap(), store);
+        assert_eq!(
+            AnswerStore::load(&dir, 1999).unwrap(),
+            AnswerStore::default()
+        );
+    }
+
+    #[test]
+    fn test_recorded_2023_answers() {
+        let store = AnswerStore::load(Path::new("answers"), 2023).unwrap();
+        assert_eq!(store.get(9, 1), Some("1853145119"));
+        assert_eq!(store.get(12, 2), None);
+    }
+
+    #[test]
+    fn test_check_against_store() {
+        let mut store = AnswerStore::default();
+        store.record(9, 1, "114");
+
+        assert_eq!(store.check(9, 1, &Answer::Signed(114)), Check::Match);
+        assert_eq!(
+            store.check(9, 1, &Answer::Integer(115)),
+            Check::Mismatch {
+                expected: "114".to_owned()
+            }
+        );
+        assert_eq!(store.check(9, 2, &Answer::Signed(2)), Check::Unrecorded);
+        assert!(store.has_day(9));
+        assert!(!store.has_day(10));
+    }
+}
@@ -1,3 +1,8 @@
+use crate::{
+    answer::Answer,
+    parse::{self, ParseError},
+    solution::Solution,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -6,69 +11,52 @@ lazy_static! {
         .expect("failed to compile regex");
 }
 
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+pub(crate) struct Day01;
+
+impl Solution for Day01 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Trebuchet?!"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
+    }
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day01.txt")
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    Ok(parse::lines(lines, calibration_value)?.into_iter().sum())
 }
 
-fn answer_part_1(lines: &str) -> usize {
-    lines
-        .split('\n')
-        .filter(|s| !s.is_empty())
-        .map(|s| {
-            let mut digits = s.chars().filter(|c| c.is_ascii_digit());
-            let first = digits.next().unwrap();
-            let maybe_last = digits.last();
-            let last = if let Some(last) = maybe_last {
-                last
-            } else {
-                first
-            };
-            let num: usize = if let Ok(num) = format!("{first}{last}").parse() {
-                num
-            } else {
-                0
-            };
-            num
-        })
-        .sum()
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    Ok(parse::lines(lines, calibration_line_to_number)?
+        .into_iter()
+        .sum())
 }
 
-fn answer_part_2(lines: &str) -> usize {
-    lines
-        .split('\n')
-        .filter(|s| !s.is_empty())
-        .map(calibration_line_to_number)
-        .sum()
+fn calibration_line_to_number(line: &str) -> Result<usize, ParseError> {
+    calibration_value(&parse_word_digits_with_dupes(line))
 }
 
-fn calibration_line_to_number(line: &str) -> usize {
-    let cleaned_calibration_value = parse_word_digits_with_dupes(line);
-    let mut digits = cleaned_calibration_value
-        .chars()
-        .filter(|c| c.is_ascii_digit());
-    let digits2 = digits.clone();
-    let first = digits.next().unwrap();
-    let maybe_last = digits.last();
-    let last = if let Some(last) = maybe_last {
-        last
-    } else {
-        first
-    };
-    let str_version = format!("{first}{last}");
-    println!(
-        "{line} - {cleaned_calibration_value} - {:?} - {str_version}",
-        digits2.collect::<Vec<char>>()
-    );
-    if let Ok(num) = str_version.parse() {
-        num
-    } else {
-        panic!("WTF");
-    }
+/// The first and last digits of the line read as a two digit number.
+fn calibration_value(line: &str) -> Result<usize, ParseError> {
+    let mut digits = line.chars().filter_map(|c| c.to_digit(10));
+    let first = digits
+        .next()
+        .ok_or_else(|| ParseError::new("expected a line with at least one digit"))?;
+    let last = digits.next_back().unwrap_or(first);
+    Ok((first * 10 + last) as usize)
 }
 
 fn parse_word_digits_with_dupes(cal_val: &str) -> String {
@@ -79,13 +67,9 @@ fn parse_word_digits_with_dupes(cal_val: &str) -> String {
     while let Some(match_val) = RE.find_at(cal_val, cur_index) {
         let (start_index, end_index) = (match_val.start(), match_val.end());
         let offset = original_length - replaced_string.len();
-        println!(
-            "0 - {offset}, {prev_end}, {start_index}, {end_index}, {cur_index}, {replaced_string}"
-        );
         let (start_offset, end_offset) = if prev_end >= start_index && start_index != 0 {
             // we're overlapping words, so we need to handle that
             //let original_offset = prev_end - (cur_index - 1);
-            println!("subtracting !!");
             (
                 end_index - (end_index - start_index + 1),
                 end_index - offset,
@@ -93,14 +77,12 @@ fn parse_word_digits_with_dupes(cal_val: &str) -> String {
         } else {
             (start_index - offset, end_index - offset)
         };
-        println!("1 - {offset}, {prev_end}, {start_offset}, {end_offset}, {cur_index}, {replaced_string}");
         let (start, _) = replaced_string.split_at(start_offset);
         let (_, end) = replaced_string.split_at(end_offset);
         cur_index = start_index + 1;
         let word_digit = parse_word_digits_safely(match_val.as_str());
 
         replaced_string = start.to_owned() + &word_digit + end;
-        println!("2 - {offset}, {prev_end}, {start_offset}, {end_offset}, {cur_index}, {replaced_string}");
         prev_end = end_offset;
     }
     replaced_string.to_owned()
@@ -121,11 +103,10 @@ fn parse_word_digits_safely(cal_val: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::day01::answer_part_2;
+    use crate::aoc2023::day01::{answer_part_1, answer_part_2};
     #[test]
     fn test_parse_calibration_line() {
-        let lines = r#"
-two1nine
+        let lines = r#"two1nine
 eightwothree
 abcone2threexyz
 xtwone3four
@@ -134,14 +115,23 @@ zoneight234
 7pqrstsixteen
 "#;
 
-        assert_eq!(answer_part_2(lines), 281);
-        let lines = r#"
-1abc2
+        assert_eq!(answer_part_2(lines), Ok(281));
+        let lines = r#"1abc2
 pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet
 "#;
-        assert_eq!(answer_part_2(lines), 142);
-        assert_eq!(answer_part_2("eighthree"), 83);
+        assert_eq!(answer_part_2(lines), Ok(142));
+        assert_eq!(answer_part_1(lines), Ok(142));
+        assert_eq!(answer_part_2("eighthree"), Ok(83));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            answer_part_1("1abc2\nabc").unwrap_err().to_string(),
+            "line 2, column 1: expected a line with at least one digit"
+        );
+        assert!(answer_part_2("1abc2\n\ntreb7uchet").is_err());
     }
 }
@@ -1,99 +1,151 @@
+use crate::{
+    answer::Answer,
+    parse::{self, ParseError},
+    solution::Solution,
+};
+
 const MAX_RED: usize = 12;
 const MAX_GREEN: usize = 13;
 const MAX_BLUE: usize = 14;
 
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
-}
+pub(crate) struct Day02;
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day02.txt")
-}
+impl Solution for Day02 {
+    fn year(&self) -> u16 {
+        2023
+    }
 
-fn get_row_power(lines: &str) -> usize {
-    let mut game_split = lines.split(':');
-    let game = game_split.next().unwrap();
-    let value = game_split.next().unwrap().trim();
-    let possible = get_min_for_colors(value);
-    println!("game: {game:?}, value: {value:?}, possible: {possible:?}");
-    possible.0 * possible.1 * possible.2
-}
+    fn day(&self) -> usize {
+        2
+    }
 
-fn get_min_for_colors(line: &str) -> (usize, usize, usize) {
-    let mut colors = (0, 0, 0);
-    let drawings = line.split(';');
-    for drawing in drawings {
-        let color_values = drawing.split(',');
-        for color_value in color_values {
-            let mut splits = color_value.split_whitespace();
-            let num: usize = splits.next().unwrap().parse().unwrap();
-            match splits.next().unwrap() {
-                "red" => colors.0 = std::cmp::max(colors.0, num),
-                "green" => colors.1 = std::cmp::max(colors.1, num),
-                "blue" => colors.2 = std::cmp::max(colors.2, num),
-                _ => panic!("couldn't find color"),
-            };
-        }
+    fn title(&self) -> &'static str {
+        "Cube Conundrum"
     }
-    colors
-}
 
-fn answer_part_2(lines: &str) -> usize {
-    lines.lines().map(get_row_power).sum()
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
+    }
 }
 
-fn answer_part_1(lines: &str) -> usize {
-    lines.lines().map(parse_game_row).sum()
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    Ok(parse::lines(lines, Game::parse)?
+        .iter()
+        .filter(|game| game.is_possible())
+        .map(|game| game.id)
+        .sum())
 }
 
-fn parse_game_row(lines: &str) -> usize {
-    let mut game_split = lines.split(':');
-    let game = get_game_value(game_split.next().unwrap());
-    let value = game_split.next().unwrap().trim();
-    let possible = are_games_possible(value);
-    println!("game: {game:?}, value: {value:?}, possible: {possible}");
-    if possible {
-        game
-    } else {
-        0
-    }
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    Ok(parse::lines(lines, Game::parse)?
+        .iter()
+        .map(|game| game.fewest_cubes().power())
+        .sum())
 }
 
-fn get_game_value(game_value: &str) -> usize {
-    let mut splits = game_value.split_whitespace();
-    let _ = splits.next();
-    let val = splits.next().unwrap();
-    let return_val: usize = val.parse().unwrap();
-    return_val
+/// One line of the record: `Game <id>: ` followed by draws separated by `; `.
+struct Game {
+    id: usize,
+    draws: Vec<Cubes>,
 }
 
-fn are_games_possible(line: &str) -> bool {
-    line.split(';').all(is_game_possible)
+impl Game {
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let (game, draws) = line.split_once(": ").ok_or_else(|| {
+            ParseError::at(
+                1,
+                line.chars().count() + 1,
+                "expected `Game <id>: ` followed by draws",
+            )
+        })?;
+        let id = game
+            .strip_prefix("Game ")
+            .ok_or_else(|| ParseError::new("expected `Game <id>`"))
+            .and_then(|id| parse::number(id).map_err(|e| e.within(1, 6)))?;
+        let mut column = game.chars().count() + 3;
+        let draws = draws
+            .split("; ")
+            .map(|draw| {
+                let cubes = Cubes::parse(draw).map_err(|e| e.within(1, column));
+                column += draw.chars().count() + 2;
+                cubes
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { id, draws })
+    }
+
+    fn is_possible(&self) -> bool {
+        self.draws.iter().all(Cubes::is_possible)
+    }
+
+    /// The fewest cubes of each colour the bag could have held.
+    fn fewest_cubes(&self) -> Cubes {
+        self.draws
+            .iter()
+            .fold(Cubes::default(), |most, draw| Cubes {
+                red: most.red.max(draw.red),
+                green: most.green.max(draw.green),
+                blue: most.blue.max(draw.blue),
+            })
+    }
 }
 
-fn is_game_possible(line: &str) -> bool {
-    line.splitn(3, ',').all(is_valid_color)
+/// How many cubes of each colour were shown at once.
+#[derive(Debug, Default, PartialEq)]
+struct Cubes {
+    red: usize,
+    green: usize,
+    blue: usize,
 }
 
-fn is_valid_color(draw: &str) -> bool {
-    let mut splits = draw.split_whitespace();
-    let num: usize = splits.next().unwrap().parse().unwrap();
-    let color_limit = match splits.next().unwrap() {
-        "blue" => MAX_BLUE,
-        "red" => MAX_RED,
-        "green" => MAX_GREEN,
-        _ => panic!("couldn't find color"),
-    };
-    num <= color_limit
+impl Cubes {
+    /// Counts like `3 blue, 4 red`, any colour left out being zero.
+    fn parse(draw: &str) -> Result<Self, ParseError> {
+        let mut cubes = Cubes::default();
+        let mut column = 1;
+        for count in draw.split(", ") {
+            let (number, colour) = count.split_once(' ').ok_or_else(|| {
+                ParseError::at(
+                    1,
+                    column,
+                    format!("expected a number and a colour, found `{count}`"),
+                )
+            })?;
+            let number = parse::number(number).map_err(|e| e.within(1, column))?;
+            let colour_column = column + count.chars().count() - colour.chars().count();
+            match colour {
+                "red" => cubes.red = number,
+                "green" => cubes.green = number,
+                "blue" => cubes.blue = number,
+                _ => {
+                    return Err(ParseError::at(
+                        1,
+                        colour_column,
+                        format!("unknown colour `{colour}`"),
+                    ))
+                }
+            }
+            column += count.chars().count() + 2;
+        }
+        Ok(cubes)
+    }
+
+    fn is_possible(&self) -> bool {
+        self.red <= MAX_RED && self.green <= MAX_GREEN && self.blue <= MAX_BLUE
+    }
+
+    fn power(&self) -> usize {
+        self.red * self.green * self.blue
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day02::{
-        answer_part_1, answer_part_2, are_games_possible, get_min_for_colors, get_row_power,
-        is_game_possible, parse_game_row,
-    };
+    use crate::aoc2023::day02::{answer_part_1, answer_part_2, Cubes, Game};
     #[test]
     fn test_all_lines() {
         let lines = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
@@ -102,63 +154,69 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
 
-        assert_eq!(answer_part_1(lines), 8);
-        assert_eq!(answer_part_2(lines), 2286);
+        assert_eq!(answer_part_1(lines), Ok(8));
+        assert_eq!(answer_part_2(lines), Ok(2286));
     }
 
     #[test]
     fn test_parse_game_row() {
-        assert_eq!(
-            parse_game_row("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"),
-            1
-        );
-        assert_eq!(
-            parse_game_row(
-                "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
-            ),
-            0
-        );
+        let game = Game::parse("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+        assert_eq!(game.id, 1);
+        assert!(game.is_possible());
+        let game =
+            Game::parse("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red")
+                .unwrap();
+        assert_eq!(game.id, 3);
+        assert!(!game.is_possible());
     }
 
     #[test]
     fn test_get_row_powers() {
+        let game = Game::parse("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
         assert_eq!(
-            get_row_power("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"),
-            48
-        );
-        assert_eq!(
-            get_row_power(
-                "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
-            ),
-            1560
+            game.fewest_cubes(),
+            Cubes {
+                red: 4,
+                green: 2,
+                blue: 6
+            }
         );
+        assert_eq!(game.fewest_cubes().power(), 48);
+        let game =
+            Game::parse("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red")
+                .unwrap();
+        assert_eq!(game.fewest_cubes().power(), 1560);
+    }
+
+    #[test]
+    fn test_is_game_possible() {
+        assert!(Cubes::parse("3 blue, 4 red").unwrap().is_possible());
+        assert!(!Cubes::parse("8 green, 6 blue, 20 red")
+            .unwrap()
+            .is_possible());
     }
 
     #[test]
-    fn test_get_min_for_colors() {
+    fn test_parse_errors() {
         assert_eq!(
-            get_min_for_colors("3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"),
-            (4, 2, 6)
+            answer_part_1("Game 1: 3 blue\nGame 2: 4 red, 1 purple")
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 18: unknown colour `purple`"
         );
         assert_eq!(
-            get_min_for_colors("8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"),
-            (20, 13, 6)
+            answer_part_2("Game 1: 3 blue; x red")
+                .unwrap_err()
+                .to_string(),
+            "line 1, column 17: expected a number, found `x`"
+        );
+        assert_eq!(
+            answer_part_1("Game one: 3 blue").unwrap_err().to_string(),
+            "line 1, column 6: expected a number, found `one`"
+        );
+        assert_eq!(
+            answer_part_1("0 3 6 9 12 15").unwrap_err().to_string(),
+            "line 1, column 14: expected `Game <id>: ` followed by draws"
         );
-    }
-
-    #[test]
-    fn test_are_games_possible() {
-        assert!(are_games_possible(
-            "3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
-        ));
-        assert!(!are_games_possible(
-            "8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
-        ));
-    }
-
-    #[test]
-    fn test_is_game_possible() {
-        assert!(is_game_possible("3 blue, 4 red"));
-        assert!(!is_game_possible("8 green, 6 blue, 20 red"));
     }
 }
@@ -1,191 +1,143 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::collections::HashMap;
+use crate::{answer::Answer, geometry::UPoint, grid::Grid, parse::ParseError, solution::Solution};
 
-lazy_static! {
-    static ref SCHEMATIC_RE: Regex = Regex::new(r"(\d+)|([\*$&\#\-=\+%/@])").unwrap();
+pub(crate) struct Day03;
+
+impl Solution for Day03 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "Gear Ratios"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
+    }
 }
 
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    let schematic = Schematic::parse(lines)?;
+    Ok(schematic
+        .parts
+        .iter()
+        .filter(|part| schematic.is_next_to_symbol(part))
+        .map(|part| part.number)
+        .sum())
 }
 
-fn answer_part_1(lines: &str) -> usize {
-    let rows = lines
-        .lines()
-        .enumerate()
-        .map(|(i, line)| get_schematic_values(i, line))
-        .collect::<Vec<Vec<SchematicValue>>>();
-    println!("{rows:?}");
-    let mut symbols: HashMap<usize, Vec<Symbol>> = HashMap::new();
-    let mut parts: HashMap<usize, Vec<PartNumber>> = HashMap::new();
-    for row_items in rows {
-        for value in row_items {
-            match value {
-                SchematicValue::Symbol(ref s) => {
-                    if let Some(r) = symbols.get_mut(&s.row) {
-                        r.push(s.clone());
-                    } else {
-                        symbols.insert(s.row, vec![s.clone()]);
-                    }
-                }
-                SchematicValue::PartNumber(ref p) => {
-                    if let Some(r) = parts.get_mut(&p.row) {
-                        r.push(p.clone());
-                    } else {
-                        parts.insert(p.row, vec![p.clone()]);
-                    }
-                }
-            };
-        }
-    }
-    let mut running_part_total = 0;
-    for (i, part_list) in parts.iter() {
-        let relevant_symbols = {
-            let mut relevant_symbols = vec![];
-            // don't include if first row
-            if i != &0 {
-                if let Some(s) = symbols.get(&(i - 1)) {
-                    relevant_symbols.append(&mut s.clone())
-                }
-            }
-            if let Some(s) = symbols.get(i) {
-                relevant_symbols.append(&mut s.clone())
-            }
-            if let Some(s) = symbols.get(&(i + 1)) {
-                relevant_symbols.append(&mut s.clone())
-            }
-            relevant_symbols
-        };
-        for part in part_list {
-            let mut part_number_valid = false;
-            for symbol in &relevant_symbols.clone() {
-                if part.start.saturating_sub(1) <= symbol.start && symbol.start <= part.end + 1 {
-                    println!(
-                        "found! row: {}, part: {}, sym_row: {}, {} <= {} <= {}",
-                        part.row, part.number, symbol.row, part.start, symbol.start, part.end
-                    );
-                    part_number_valid = true;
-                    break;
-                }
-            }
-            if part_number_valid {
-                running_part_total += part.number;
-            }
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    let schematic = Schematic::parse(lines)?;
+    let mut running_gear_ratio = 0;
+    for gear in schematic.symbols.iter().filter(|s| s.symbol == '*') {
+        let related_parts: Vec<&PartNumber> = schematic
+            .parts
+            .iter()
+            .filter(|part| part.is_adjacent(UPoint::from((gear.row, gear.start))))
+            .collect();
+        if related_parts.len() == 2 {
+            let gear_ratio: usize = related_parts.iter().map(|p| p.number).product();
+            running_gear_ratio += gear_ratio;
         }
     }
-    running_part_total
+    Ok(running_gear_ratio)
 }
 
-fn answer_part_2(lines: &str) -> usize {
-    let rows = lines
-        .lines()
-        .enumerate()
-        .map(|(i, line)| get_schematic_values(i, line))
-        .collect::<Vec<Vec<SchematicValue>>>();
-    let mut gears: HashMap<usize, Vec<Symbol>> = HashMap::new();
-    let mut parts: HashMap<usize, Vec<PartNumber>> = HashMap::new();
-    for row_items in rows {
-        for value in row_items {
-            match value {
-                SchematicValue::Symbol(ref s) => {
-                    if s.symbol == '*' {
-                        if let Some(r) = gears.get_mut(&s.row) {
-                            r.push(s.clone());
-                        } else {
-                            gears.insert(s.row, vec![s.clone()]);
-                        }
-                    }
-                }
-                SchematicValue::PartNumber(ref p) => {
-                    if let Some(r) = parts.get_mut(&p.row) {
-                        r.push(p.clone());
-                    } else {
-                        parts.insert(p.row, vec![p.clone()]);
-                    }
-                }
-            };
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Cell {
+    Empty,
+    Digit(usize),
+    Symbol(char),
+}
+
+impl TryFrom<char> for Cell {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Cell::Empty),
+            '0'..='9' => Ok(Cell::Digit(c as usize - '0' as usize)),
+            _ if c.is_ascii_punctuation() => Ok(Cell::Symbol(c)),
+            _ => Err(ParseError::new(format!(
+                "unexpected `{c}` in the schematic, expected a digit, `.` or a symbol"
+            ))),
         }
     }
-    let mut running_gear_ratio = 0;
-    for (i, gear_list) in gears.iter() {
-        let relevant_parts = {
-            let mut relevant_symbols = vec![];
-            // don't include if first row
-            if i != &0 {
-                if let Some(p) = parts.get(&(i - 1)) {
-                    relevant_symbols.append(&mut p.clone())
-                }
-            }
-            if let Some(p) = parts.get(i) {
-                relevant_symbols.append(&mut p.clone())
-            }
-            if let Some(p) = parts.get(&(i + 1)) {
-                relevant_symbols.append(&mut p.clone())
-            }
-            relevant_symbols
-        };
-        for gear in gear_list {
-            let mut related_parts = vec![];
-            for part in &relevant_parts.clone() {
-                if part.start.saturating_sub(1) <= gear.start && gear.start <= part.end + 1 {
-                    println!(
-                        "found! row: {}, part: {}, sym_row: {}, {} <= {} <= {}",
-                        part.row, part.number, gear.row, part.start, gear.start, part.end
-                    );
-                    related_parts.push(part.clone());
+}
+
+struct Schematic {
+    grid: Grid<Cell>,
+    parts: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+}
+
+impl Schematic {
+    fn parse(lines: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse(lines)?;
+        let mut parts = vec![];
+        let mut symbols = vec![];
+        for row in 0..grid.rows() {
+            for value in get_schematic_values(row, grid.row(row)) {
+                match value {
+                    SchematicValue::PartNumber(p) => parts.push(p),
+                    SchematicValue::Symbol(s) => symbols.push(s),
                 }
             }
-            if related_parts.len() == 2 {
-                let gear_ratio: usize = related_parts.iter().map(|p| p.number).product();
-                running_gear_ratio += gear_ratio;
-            }
         }
+        Ok(Self {
+            grid,
+            parts,
+            symbols,
+        })
     }
-    running_gear_ratio
-}
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day03.txt")
+    fn is_next_to_symbol(&self, part: &PartNumber) -> bool {
+        (part.start..=part.end)
+            .flat_map(|col| self.grid.neighbours8((part.row, col)))
+            .any(|coord| matches!(self.grid[coord], Cell::Symbol(_)))
+    }
 }
 
-fn get_schematic_values(row: usize, line: &str) -> Vec<SchematicValue> {
-    SCHEMATIC_RE
-        .find_iter(line)
-        .map(|val| {
-            let s = val.as_str();
-            if s.starts_with('*')
-                || s.starts_with('#')
-                || s.starts_with('+')
-                || s.starts_with('&')
-                || s.starts_with('$')
-                || s.starts_with('-')
-                || s.starts_with('+')
-                || s.starts_with('%')
-                || s.starts_with('@')
-                || s.starts_with('=')
-                || s.starts_with('/')
-            {
-                SchematicValue::Symbol(Symbol {
-                    row,
-                    symbol: s.chars().next().unwrap(),
-                    start: val.start(),
-                })
-            } else {
-                let num: usize = match s.parse() {
-                    Ok(n) => n,
-                    Err(_) => panic!("did not expect number {s}"),
-                };
-                SchematicValue::PartNumber(PartNumber {
+/// The numbers and symbols in one row of the schematic, left to right.
+fn get_schematic_values(row: usize, cells: &[Cell]) -> Vec<SchematicValue> {
+    let mut values = vec![];
+    let mut number: Option<PartNumber> = None;
+    for (column, cell) in cells.iter().enumerate() {
+        match (cell, number.as_mut()) {
+            (Cell::Digit(digit), Some(part)) => {
+                part.number = part.number * 10 + digit;
+                part.end = column;
+            }
+            (Cell::Digit(digit), None) => {
+                number = Some(PartNumber {
                     row,
-                    number: num,
-                    start: val.start(),
-                    end: val.end() - 1,
+                    number: *digit,
+                    start: column,
+                    end: column,
                 })
             }
-        })
-        .collect()
+            (_, _) => {
+                values.extend(number.take().map(SchematicValue::PartNumber));
+                if let Cell::Symbol(symbol) = cell {
+                    values.push(SchematicValue::Symbol(Symbol {
+                        row,
+                        symbol: *symbol,
+                        start: column,
+                    }));
+                }
+            }
+        }
+    }
+    values.extend(number.take().map(SchematicValue::PartNumber));
+    values
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -202,6 +154,13 @@ struct PartNumber {
     end: usize,
 }
 
+impl PartNumber {
+    /// Whether any digit touches `point`, diagonally included.
+    fn is_adjacent(&self, point: UPoint) -> bool {
+        (self.start..=self.end).any(|x| UPoint { x, y: self.row }.chebyshev(point) == 1)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 struct Symbol {
     row: usize,
@@ -211,16 +170,17 @@ struct Symbol {
 
 #[cfg(test)]
 mod tests {
-    use crate::day03::{
-        answer_part_1, answer_part_2, get_input_string, get_schematic_values, PartNumber,
-        SchematicValue, Symbol,
+    use crate::aoc2023::day03::{
+        answer_part_1, answer_part_2, get_schematic_values, PartNumber, SchematicValue, Symbol,
     };
+    use crate::input::{self, InputSource};
+    use crate::parse;
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 3, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 527364);
-        assert_eq!(answer_part_2(lines), 79026871);
+        assert_eq!(answer_part_1(lines), Ok(527364));
+        assert_eq!(answer_part_2(lines), Ok(79026871));
     }
 
     #[test]
@@ -236,14 +196,18 @@ mod tests {
 ...$.*....
 .664.598.."#;
 
-        assert_eq!(answer_part_1(lines), 4361);
-        assert_eq!(answer_part_2(lines), 467835);
+        assert_eq!(answer_part_1(lines), Ok(4361));
+        assert_eq!(answer_part_2(lines), Ok(467835));
+        assert_eq!(
+            answer_part_1("467..\n...a.").unwrap_err().to_string(),
+            "line 2, column 4: unexpected `a` in the schematic, expected a digit, `.` or a symbol"
+        );
     }
 
     #[test]
     fn test_get_schematic_values() {
         let line = "467..114..";
-        let values = get_schematic_values(0, line);
+        let values = get_schematic_values(0, &parse::chars(line).unwrap());
         let expected_values = vec![
             SchematicValue::PartNumber(PartNumber {
                 row: 0,
@@ -260,7 +224,7 @@ mod tests {
         ];
         assert_eq!(values, expected_values);
         let line = "617*......";
-        let values = get_schematic_values(0, line);
+        let values = get_schematic_values(0, &parse::chars(line).unwrap());
         let expected_values = vec![
             SchematicValue::PartNumber(PartNumber {
                 row: 0,
@@ -276,7 +240,7 @@ mod tests {
         ];
         assert_eq!(values, expected_values);
         let line = ".....+.58.";
-        let values = get_schematic_values(0, line);
+        let values = get_schematic_values(0, &parse::chars(line).unwrap());
         let expected_values = vec![
             SchematicValue::Symbol(Symbol {
                 row: 0,
@@ -1,8 +1,28 @@
+use crate::{answer::Answer, parse::ParseError, solution::Solution};
 use std::collections::{HashMap, HashSet};
 
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+pub(crate) struct Day04;
+
+impl Solution for Day04 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        4
+    }
+
+    fn title(&self) -> &'static str {
+        "Scratchcards"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_1(input).into())
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_2(input).into())
+    }
 }
 
 fn answer_part_1(lines: &str) -> usize {
@@ -44,10 +64,6 @@ fn answer_part_2(lines: &str) -> usize {
     total
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day04.txt")
-}
-
 #[derive(Debug, PartialEq)]
 struct ScratchCard {
     number: usize,
@@ -96,11 +112,12 @@ impl ScratchCard {
 
 #[cfg(test)]
 mod tests {
-    use crate::day04::{answer_part_1, answer_part_2, get_input_string, ScratchCard};
+    use crate::aoc2023::day04::{answer_part_1, answer_part_2, ScratchCard};
+    use crate::input::{self, InputSource};
     use std::collections::HashSet;
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 4, &InputSource::default()).unwrap();
 
         assert_eq!(answer_part_1(lines), 23847);
         assert_eq!(answer_part_2(lines), 8570000);
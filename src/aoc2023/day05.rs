@@ -1,21 +1,54 @@
-use rayon::prelude::*;
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
-}
+use crate::{
+    answer::Answer,
+    interval::{Interval, IntervalSet, OffsetMap},
+    parse::{self, ParseError},
+    solution::Solution,
+};
+pub(crate) struct Day05;
+
+impl Solution for Day05 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        5
+    }
 
-fn answer_part_1(lines: &str) -> usize {
-    FullMap::parse(lines).min_location_for_seeds(true)
+    fn title(&self) -> &'static str {
+        "If You Give A Seed A Fertilizer"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
+    }
 }
 
-fn answer_part_2(lines: &str) -> usize {
-    FullMap::parse(lines).min_location_for_seeds(false)
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    Ok(FullMap::parse(lines)?.min_location_for_seeds(true))
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day05.txt")
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    Ok(FullMap::parse(lines)?.min_location_for_seeds(false))
 }
 
+const SEEDS: &str = "seeds:";
+
+/// The maps in the order they appear in the almanac, each applied to the last one's output.
+const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
 #[derive(Debug, PartialEq)]
 struct FullMap {
     seeds: Vec<usize>,
@@ -29,209 +62,98 @@ struct FullMap {
 }
 
 impl FullMap {
-    fn parse(input: &str) -> Self {
-        let mut lines = input.lines();
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line));
         let seeds = lines
             .next()
-            .expect("first line should be seeds")
-            .split_once(':')
-            .unwrap()
-            .1
-            .split_whitespace()
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect();
-
-        // moving on
-        lines.next();
-
-        // should be seed-to-soil, skip
-        lines.next();
-
-        let mut seed_to_soil = vec![];
-        for line in lines.by_ref() {
-            // move on to next section
-            if line.is_empty() {
-                break;
-            }
-
-            seed_to_soil.push(PlacementOffset::parse(line));
-        }
-
-        // should be soil-to-fertilizer, skip
-        lines.next();
-
-        let mut soil_to_fertilizer = vec![];
-        for line in lines.by_ref() {
-            // move on to next section
-            if line.is_empty() {
-                break;
-            }
-
-            soil_to_fertilizer.push(PlacementOffset::parse(line));
-        }
-
-        // should be soil-to-fertilizer, skip
-        lines.next();
-
-        let mut fertilizer_to_water = vec![];
-        for line in lines.by_ref() {
-            // move on to next section
-            if line.is_empty() {
-                break;
+            .and_then(|(_, line)| line.strip_prefix(SEEDS))
+            .ok_or_else(|| {
+                ParseError::new(format!("expected the first line to start with `{SEEDS}`"))
+            })?;
+        let seeds = parse::fields(seeds)
+            .map(|(column, seed)| {
+                parse::number(seed).map_err(|e| e.within(1, SEEDS.len() + column))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut sections = MAP_NAMES.iter().map(|name| {
+            let (number, header) = lines
+                .by_ref()
+                .find(|(_, line)| !line.is_empty())
+                .ok_or_else(|| {
+                    ParseError::at(
+                        input.lines().count() + 1,
+                        1,
+                        format!("the input ends before the `{name} map:` section"),
+                    )
+                })?;
+            if header != format!("{name} map:") {
+                return Err(ParseError::at(number, 1, format!("expected `{name} map:`")));
             }
-
-            fertilizer_to_water.push(PlacementOffset::parse(line));
-        }
-
-        // should be soil-to-fertilizer, skip
-        lines.next();
-
-        let mut water_to_light = vec![];
-        for line in lines.by_ref() {
-            // move on to next section
-            if line.is_empty() {
-                break;
-            }
-
-            water_to_light.push(PlacementOffset::parse(line));
-        }
-
-        // should be soil-to-fertilizer, skip
-        lines.next();
-
-        let mut light_to_temperature = vec![];
-        for line in lines.by_ref() {
-            // move on to next section
-            if line.is_empty() {
-                break;
-            }
-
-            light_to_temperature.push(PlacementOffset::parse(line));
-        }
-
-        // should be soil-to-fertilizer, skip
-        lines.next();
-
-        let mut temperature_to_humidity = vec![];
-        for line in lines.by_ref() {
-            // move on to next section
-            if line.is_empty() {
-                break;
-            }
-
-            temperature_to_humidity.push(PlacementOffset::parse(line));
-        }
-
-        // should be soil-to-fertilizer, skip
-        lines.next();
-
-        let mut humidity_to_location = vec![];
-        for line in lines.by_ref() {
-            // move on to next section
-            if line.is_empty() {
-                break;
-            }
-
-            humidity_to_location.push(PlacementOffset::parse(line));
-        }
-
-        FullMap {
+            lines
+                .by_ref()
+                .take_while(|(_, line)| !line.is_empty())
+                .map(|(number, line)| PlacementOffset::parse(line).map_err(|e| e.within(number, 1)))
+                .collect::<Result<Vec<_>, _>>()
+        });
+        let mut next_section = || sections.next().expect("one section per map name");
+
+        Ok(FullMap {
             seeds,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
-        }
-    }
-
-    fn expanded_seed_list(&self) -> Vec<usize> {
-        let mut seed_iter = self.seeds.iter();
-        let mut new_seeds = vec![];
-
-        while let Some(seed) = seed_iter.next() {
-            let count = seed_iter.next().unwrap();
-            let mut all_seeds: Vec<usize> = (*seed..(*seed + count)).collect();
-            new_seeds.append(&mut all_seeds);
-        }
-        new_seeds
+            seed_to_soil: next_section()?,
+            soil_to_fertilizer: next_section()?,
+            fertilizer_to_water: next_section()?,
+            water_to_light: next_section()?,
+            light_to_temperature: next_section()?,
+            temperature_to_humidity: next_section()?,
+            humidity_to_location: next_section()?,
+        })
     }
 
-    fn min_location_for_seeds(&self, v1: bool) -> usize {
-        let mut location = usize::MAX;
-        let seeds = if v1 {
-            self.seeds.clone()
+    /// Each seed on its own for part one, or the seeds described by pairs of start and
+    /// length for part two.
+    fn seed_ranges(&self, v1: bool) -> IntervalSet {
+        if v1 {
+            self.seeds
+                .iter()
+                .map(|seed| Interval::with_len(*seed as i64, 1))
+                .collect()
         } else {
-            self.expanded_seed_list()
-        };
-        let total = seeds.len();
-
-        let mut num_processed = 0;
-        let locations: Vec<usize> = seeds
-            .par_iter()
-            .map(|seed| self.location_from_seed(*seed))
-            .collect();
-        for new_local in &locations {
-            if new_local < &location {
-                location = *new_local;
-            }
-
-            num_processed += 1;
-            if num_processed % 10000 == 0 {
-                println!(
-                    "{}/{} ({}) seeds processed",
-                    num_processed,
-                    total,
-                    num_processed as f64 / total as f64
-                );
-            }
+            self.seeds
+                .chunks_exact(2)
+                .map(|pair| Interval::with_len(pair[0] as i64, pair[1] as i64))
+                .collect()
         }
-        location
     }
 
-    fn location_from_seed(&self, seed: usize) -> usize {
-        let soil = self
-            .seed_to_soil
-            .iter()
-            .find(|offset| offset.contains_item(seed))
-            .map_or(seed, |offset| offset.item_location(seed));
-
-        let fertilizer = self
-            .soil_to_fertilizer
-            .iter()
-            .find(|offset| offset.contains_item(soil))
-            .map_or(soil, |offset| offset.item_location(soil));
-
-        let water = self
-            .fertilizer_to_water
-            .iter()
-            .find(|offset| offset.contains_item(fertilizer))
-            .map_or(fertilizer, |offset| offset.item_location(fertilizer));
-
-        let light = self
-            .water_to_light
-            .iter()
-            .find(|offset| offset.contains_item(water))
-            .map_or(water, |offset| offset.item_location(water));
-
-        let temperature = self
-            .light_to_temperature
-            .iter()
-            .find(|offset| offset.contains_item(light))
-            .map_or(light, |offset| offset.item_location(light));
+    fn layers(&self) -> [&[PlacementOffset]; 7] {
+        [
+            &self.seed_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_humidity,
+            &self.humidity_to_location,
+        ]
+    }
 
-        let humidity = self
-            .temperature_to_humidity
-            .iter()
-            .find(|offset| offset.contains_item(temperature))
-            .map_or(temperature, |offset| offset.item_location(temperature));
+    /// Push whole ranges of values through every map in turn, splitting them where the
+    /// offsets start and end.
+    fn locations(&self, seeds: IntervalSet) -> IntervalSet {
+        self.layers().iter().fold(seeds, |values, layer| {
+            let map: OffsetMap = layer.iter().map(PlacementOffset::piece).collect();
+            map.map(&values)
+        })
+    }
 
-        self.humidity_to_location
-            .iter()
-            .find(|offset| offset.contains_item(humidity))
-            .map_or(humidity, |offset| offset.item_location(humidity))
+    fn min_location_for_seeds(&self, v1: bool) -> usize {
+        self.locations(self.seed_ranges(v1))
+            .min()
+            .map_or(usize::MAX, |location| location as usize)
     }
 }
 
@@ -243,56 +165,44 @@ struct PlacementOffset {
 }
 
 impl PlacementOffset {
-    fn parse(line: &str) -> Self {
-        let mut parts = line.split_whitespace();
-        let destination = parts
-            .next()
-            .expect("should always have destination")
-            .parse()
-            .expect("destination should be number");
-        let source = parts
-            .next()
-            .expect("should always have source")
-            .parse()
-            .expect("source should be number");
-        let count = parts
-            .next()
-            .expect("should always have count")
-            .parse()
-            .expect("count should be number");
-        PlacementOffset {
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let mut parts = parse::fields(line);
+        let mut next = |what: &str| -> Result<usize, ParseError> {
+            let (column, part) = parts.next().ok_or_else(|| {
+                ParseError::at(1, line.chars().count() + 1, format!("missing the {what}"))
+            })?;
+            parse::number(part).map_err(|e| e.within(1, column))
+        };
+        let destination = next("destination")?;
+        let source = next("source")?;
+        let count = next("count")?;
+        Ok(PlacementOffset {
             source,
             destination,
             count,
-        }
+        })
     }
 
-    fn contains_item(&self, item: usize) -> bool {
-        (self.source..(self.source + self.count)).contains(&item)
-    }
-
-    fn item_location(&self, item: usize) -> usize {
-        // don't want to deal with casting to isize
-        if self.destination > self.source {
-            self.destination
-                .saturating_sub(self.source)
-                .saturating_add(item)
-        } else {
-            item.saturating_sub(self.source.saturating_sub(self.destination))
-        }
+    /// The source range and how far it moves.
+    fn piece(&self) -> (Interval, i64) {
+        (
+            Interval::with_len(self.source as i64, self.count as i64),
+            self.destination as i64 - self.source as i64,
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day05::{answer_part_1, answer_part_2, get_input_string, FullMap, PlacementOffset};
+    use crate::aoc2023::day05::{answer_part_1, answer_part_2, FullMap, PlacementOffset};
+    use crate::input::{self, InputSource};
+    use crate::interval::{Interval, IntervalSet, OffsetMap};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 5, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 26273516);
-        // run this if you dare, it uses all cores and still takes a while
-        //assert_eq!(answer_part_2(lines), 34039469);
+        assert_eq!(answer_part_1(lines), Ok(26273516));
+        assert_eq!(answer_part_2(lines), Ok(34039469));
     }
     const EXAMPLE_LINES: &str = r#"seeds: 79 14 55 13
 
@@ -332,15 +242,15 @@ humidity-to-location map:
 
     #[test]
     fn test_short_example() {
-        assert_eq!(answer_part_1(EXAMPLE_LINES), 35);
-        assert_eq!(answer_part_2(EXAMPLE_LINES), 46);
+        assert_eq!(answer_part_1(EXAMPLE_LINES), Ok(35));
+        assert_eq!(answer_part_2(EXAMPLE_LINES), Ok(46));
     }
 
     #[test]
     fn test_parsing() {
         let offset_str = "50 98 2";
         assert_eq!(
-            PlacementOffset::parse(offset_str),
+            PlacementOffset::parse(offset_str).unwrap(),
             PlacementOffset {
                 destination: 50,
                 source: 98,
@@ -349,7 +259,7 @@ humidity-to-location map:
         );
 
         assert_eq!(
-            FullMap::parse(EXAMPLE_LINES),
+            FullMap::parse(EXAMPLE_LINES).unwrap(),
             FullMap {
                 seeds: vec![79, 14, 55, 13],
                 seed_to_soil: vec![
@@ -460,24 +370,59 @@ humidity-to-location map:
         );
     }
 
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            PlacementOffset::parse("50 98").unwrap_err().to_string(),
+            "line 1, column 6: missing the count"
+        );
+        assert_eq!(
+            FullMap::parse(&EXAMPLE_LINES.replace("39 0 15", "39 O 15"))
+                .unwrap_err()
+                .to_string(),
+            "line 10, column 4: expected a number, found `O`"
+        );
+        assert_eq!(
+            FullMap::parse("seeds: 79 14 5x").unwrap_err().to_string(),
+            "line 1, column 14: expected a number, found `5x`"
+        );
+        let truncated = &EXAMPLE_LINES[..EXAMPLE_LINES.find("light-to-temperature").unwrap()];
+        assert_eq!(
+            FullMap::parse(truncated).unwrap_err().to_string(),
+            "line 22, column 1: the input ends before the `light-to-temperature map:` section"
+        );
+    }
+
     #[test]
     fn test_offset_calcs() {
-        let map = FullMap::parse(EXAMPLE_LINES);
+        let map = FullMap::parse(EXAMPLE_LINES).unwrap();
+        let seed = |seed| IntervalSet::from_iter([Interval::with_len(seed, 1)]);
 
-        assert!(map.seed_to_soil[1].contains_item(79));
-        assert_eq!(map.seed_to_soil[1].item_location(79), 81);
-        assert_eq!(map.location_from_seed(79), 82);
+        let seed_to_soil: OffsetMap = map
+            .seed_to_soil
+            .iter()
+            .map(PlacementOffset::piece)
+            .collect();
+        assert_eq!(map.seed_to_soil[1].piece(), (Interval::new(50, 98), 2));
+        assert_eq!(seed_to_soil.map(&seed(79)), seed(81));
+        assert_eq!(map.locations(seed(79)), seed(82));
     }
 
     #[test]
-    fn test_expanded_seed_list() {
-        let map = FullMap::parse(EXAMPLE_LINES);
+    fn test_seed_ranges() {
+        let map = FullMap::parse(EXAMPLE_LINES).unwrap();
 
         assert_eq!(
-            map.expanded_seed_list(),
-            vec![
-                79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 55, 56, 57, 58, 59, 60, 61,
-                62, 63, 64, 65, 66, 67,
+            map.seed_ranges(false).intervals(),
+            [Interval::new(55, 68), Interval::new(79, 93)]
+        );
+        // neighbouring seeds share an interval
+        assert_eq!(
+            map.seed_ranges(true).intervals(),
+            [
+                Interval::new(13, 15),
+                Interval::new(55, 56),
+                Interval::new(79, 80)
             ]
         );
     }
@@ -1,6 +1,27 @@
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+use crate::{answer::Answer, parse::ParseError, solution::Solution};
+
+pub(crate) struct Day06;
+
+impl Solution for Day06 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Wait For It"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_1(input).into())
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_2(input).into())
+    }
 }
 
 fn answer_part_1(lines: &str) -> usize {
@@ -84,16 +105,13 @@ fn calculate_ways_to_beat_record(time: usize, distance: usize) -> usize {
         .count()
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day06.txt")
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::day06::{answer_part_1, answer_part_2, get_input_string, Races};
+    use crate::aoc2023::day06::{answer_part_1, answer_part_2, Races};
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 6, &InputSource::default()).unwrap();
 
         assert_eq!(answer_part_1(lines), 131376);
         assert_eq!(answer_part_2(lines), 34123437);
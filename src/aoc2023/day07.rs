@@ -1,12 +1,32 @@
+use crate::{answer::Answer, parse::ParseError, solution::Solution};
 use itertools::Itertools;
 use std::{
     cmp::Ordering,
     fmt::{Debug, Display},
 };
 
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+pub(crate) struct Day07;
+
+impl Solution for Day07 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        7
+    }
+
+    fn title(&self) -> &'static str {
+        "Camel Cards"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_1(input).into())
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_2(input).into())
+    }
 }
 
 fn answer_part_1(lines: &str) -> usize {
@@ -24,9 +44,6 @@ fn calculate_total_winnings<T: Ord + Debug + BaseRanking + Clone + From<Vec<Card
 ) -> usize {
     let mut sorted_hands = hands.clone();
     sorted_hands.sort();
-    for hand in &sorted_hands {
-        println!("{hand}");
-    }
     sorted_hands
         .iter()
         .enumerate()
@@ -34,10 +51,6 @@ fn calculate_total_winnings<T: Ord + Debug + BaseRanking + Clone + From<Vec<Card
         .sum()
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day07.txt")
-}
-
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 enum Card {
     Ace = 14,
@@ -280,13 +293,13 @@ impl<T: Ord + Debug + BaseRanking + Clone + From<Vec<Card>>> HandBid<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::day07::{
-        answer_part_1, answer_part_2, calculate_total_winnings, get_input_string, Card, Hand,
-        HandBid,
+    use crate::aoc2023::day07::{
+        answer_part_1, answer_part_2, calculate_total_winnings, Card, Hand, HandBid,
     };
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 7, &InputSource::default()).unwrap();
 
         assert_eq!(answer_part_1(lines), 249726565);
         assert_eq!(answer_part_2(lines), 251135960);
@@ -1,9 +1,29 @@
+use crate::{answer::Answer, parse::ParseError, solution::Solution};
 use rayon::prelude::*;
 use std::collections::HashMap;
 
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+pub(crate) struct Day08;
+
+impl Solution for Day08 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        8
+    }
+
+    fn title(&self) -> &'static str {
+        "Haunted Wasteland"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_1(input).into())
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_2(input).into())
+    }
 }
 
 fn answer_part_1(lines: &str) -> usize {
@@ -18,10 +38,6 @@ fn answer_part_2(lines: &str) -> usize {
     map.count_ghost_steps_on_path()
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day08.txt")
-}
-
 struct Map {
     instruction_list: InstructionList,
     path: String,
@@ -82,7 +98,6 @@ impl Map {
             .filter(|k| k.ends_with('A'))
             .cloned()
             .collect();
-        println!("{locations:?}");
 
         let min_to_zs: Vec<usize> = locations
             .par_iter()
@@ -139,10 +154,11 @@ struct InstructionDestination {
 
 #[cfg(test)]
 mod tests {
-    use crate::day08::{answer_part_1, answer_part_2, get_input_string};
+    use crate::aoc2023::day08::{answer_part_1, answer_part_2};
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 8, &InputSource::default()).unwrap();
 
         assert_eq!(answer_part_1(lines), 17873);
         assert_eq!(answer_part_2(lines), 15746133679061);
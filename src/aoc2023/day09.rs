@@ -1,28 +1,45 @@
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+use crate::{
+    answer::Answer,
+    parse::{self, ParseError},
+    solution::Solution,
+};
+
+pub(crate) struct Day09;
+
+impl Solution for Day09 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        9
+    }
+
+    fn title(&self) -> &'static str {
+        "Mirage Maintenance"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
+    }
 }
 
-fn answer_part_1(lines: &str) -> usize {
-    let answer: isize = lines
-        .lines()
-        .map(SensorReport::parse)
+fn answer_part_1(lines: &str) -> Result<isize, ParseError> {
+    Ok(parse::lines(lines, SensorReport::parse)?
+        .iter()
         .map(|sr| sr.get_next_value())
-        .sum();
-    answer as usize
+        .sum())
 }
 
-fn answer_part_2(lines: &str) -> usize {
-    let answer: isize = lines
-        .lines()
-        .map(SensorReport::parse)
+fn answer_part_2(lines: &str) -> Result<isize, ParseError> {
+    Ok(parse::lines(lines, SensorReport::parse)?
+        .iter()
         .map(|sr| sr.get_previous_value())
-        .sum();
-    answer as usize
-}
-
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day09.txt")
+        .sum())
 }
 
 struct SensorReport {
@@ -30,54 +47,50 @@ struct SensorReport {
 }
 
 impl SensorReport {
-    fn parse(line: &str) -> Self {
-        Self {
-            history: line
-                .split_whitespace()
-                .map(|s| s.parse().unwrap())
-                .collect(),
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let history: Vec<isize> = parse::fields(line)
+            .map(|(column, field)| parse::number(field).map_err(|e| e.within(1, column)))
+            .collect::<Result<_, _>>()?;
+        if history.is_empty() {
+            return Err(ParseError::new("expected a history of numbers"));
         }
+        Ok(Self { history })
     }
 
     fn get_next_value(&self) -> isize {
         let mut diffs = self.history.clone();
-        let mut found_zeros = false;
-        let mut last_values = vec![*self.history.last().unwrap()];
-        while !found_zeros {
+        let mut last_values = vec![];
+        while diffs.iter().any(|n| n != &0) {
+            last_values.push(diffs[diffs.len() - 1]);
             diffs = diffs.windows(2).map(|w| w[1] - w[0]).collect();
-            last_values.push(*diffs.last().unwrap());
-            found_zeros = diffs.iter().all(|n| n == &0);
         }
-        last_values.iter().copied().reduce(|a, b| a + b).unwrap()
+        last_values.iter().sum()
     }
 
     fn get_previous_value(&self) -> isize {
         let mut diffs = self.history.clone();
-        let mut found_zeros = false;
-        let mut first_values = vec![*self.history.first().unwrap()];
-        while !found_zeros {
+        let mut first_values = vec![];
+        while diffs.iter().any(|n| n != &0) {
+            first_values.push(diffs[0]);
             diffs = diffs.windows(2).map(|w| w[1] - w[0]).collect();
-            first_values.push(*diffs.first().unwrap());
-            found_zeros = diffs.iter().all(|n| n == &0);
         }
         first_values
             .iter()
             .rev()
-            .copied()
-            .reduce(|a, b| b - a)
-            .unwrap()
+            .fold(0, |below, first| first - below)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day09::{answer_part_1, answer_part_2, get_input_string};
+    use crate::aoc2023::day09::{answer_part_1, answer_part_2};
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 9, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 1853145119);
-        assert_eq!(answer_part_2(lines), 923);
+        assert_eq!(answer_part_1(lines), Ok(1853145119));
+        assert_eq!(answer_part_2(lines), Ok(923));
     }
 
     const SAMPLE_REPORT: &str = r"0 3 6 9 12 15
@@ -87,7 +100,21 @@ mod tests {
 
     #[test]
     fn test_oasis_predictions() {
-        assert_eq!(answer_part_1(SAMPLE_REPORT), 114);
-        assert_eq!(answer_part_2(SAMPLE_REPORT), 2);
+        assert_eq!(answer_part_1(SAMPLE_REPORT), Ok(114));
+        assert_eq!(answer_part_2(SAMPLE_REPORT), Ok(2));
+        // a single reading stays put
+        assert_eq!(answer_part_1("7"), Ok(7));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            answer_part_1("0 3 6\n1 3 x 10").unwrap_err().to_string(),
+            "line 2, column 5: expected a number, found `x`"
+        );
+        assert_eq!(
+            answer_part_2("0 3 6\n\n1 3").unwrap_err().to_string(),
+            "line 2, column 1: expected a history of numbers"
+        );
     }
 }
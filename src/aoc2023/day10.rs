@@ -1,39 +1,43 @@
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
-}
+use crate::{
+    answer::Answer,
+    geometry::{Direction, UPoint},
+    grid::{Coord, Grid},
+    parse::ParseError,
+    solution::Solution,
+};
+
+pub(crate) struct Day10;
+
+impl Solution for Day10 {
+    fn year(&self) -> u16 {
+        2023
+    }
 
-fn answer_part_1(lines: &str) -> usize {
-    let map = Map::parse(lines);
-    map.steps_along_path() / 2
-}
+    fn day(&self) -> usize {
+        10
+    }
 
-fn answer_part_2(lines: &str) -> usize {
-    let map = Map::parse(lines);
-    map.count_all_inside_coords()
-}
+    fn title(&self) -> &'static str {
+        "Pipe Maze"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day10.txt")
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
+    }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    let map = Map::parse(lines)?;
+    Ok(map.walk_loop()?.len() / 2)
 }
 
-impl Direction {
-    fn opposite(&self) -> Self {
-        match self {
-            Direction::North => Direction::South,
-            Direction::South => Direction::North,
-            Direction::East => Direction::West,
-            Direction::West => Direction::East,
-        }
-    }
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    let map = Map::parse(lines)?;
+    Ok(map.count_all_inside_coords(&map.walk_loop()?))
 }
 
 // `|` is a *vertical pipe* connecting north and south.
@@ -56,21 +60,27 @@ enum Pipe {
     Start,
 }
 
-impl Pipe {
-    fn parse(item: char) -> Self {
+impl TryFrom<char> for Pipe {
+    type Error = ParseError;
+
+    fn try_from(item: char) -> Result<Self, Self::Error> {
         match item {
-            '|' => Pipe::Vertical,
-            '-' => Pipe::Horizontal,
-            'L' => Pipe::NorthEast,
-            'J' => Pipe::NorthWest,
-            '7' => Pipe::SouthWest,
-            'F' => Pipe::SouthEast,
-            '.' => Pipe::Ground,
-            'S' => Pipe::Start,
-            _ => panic!("weird map"),
+            '|' => Ok(Pipe::Vertical),
+            '-' => Ok(Pipe::Horizontal),
+            'L' => Ok(Pipe::NorthEast),
+            'J' => Ok(Pipe::NorthWest),
+            '7' => Ok(Pipe::SouthWest),
+            'F' => Ok(Pipe::SouthEast),
+            '.' => Ok(Pipe::Ground),
+            'S' => Ok(Pipe::Start),
+            _ => Err(ParseError::new(format!(
+                "weird map tile `{item}`, expected one of `|-LJ7F.S`"
+            ))),
         }
     }
+}
 
+impl Pipe {
     fn connects_to(&self) -> Vec<Direction> {
         match *self {
             Pipe::Vertical => vec![Direction::North, Direction::South],
@@ -80,46 +90,45 @@ impl Pipe {
             Pipe::SouthWest => vec![Direction::South, Direction::West],
             Pipe::SouthEast => vec![Direction::South, Direction::East],
             Pipe::Ground => vec![],
-            Pipe::Start => vec![
-                Direction::North,
-                Direction::South,
-                Direction::East,
-                Direction::West,
-            ],
+            Pipe::Start => Direction::ALL.to_vec(),
         }
     }
 }
 
 struct Map {
-    grid: Vec<Vec<Pipe>>,
-    row_count: usize,
-    col_count: usize,
+    grid: Grid<Pipe>,
+    start: Coord,
 }
 
 impl Map {
-    fn parse(lines: &str) -> Self {
-        let grid: Vec<Vec<Pipe>> = lines.lines().map(Map::parse_line).collect();
-        let row_count = grid.len();
-        let col_count = grid.first().unwrap().len();
-        Self {
-            grid,
-            row_count,
-            col_count,
-        }
-    }
-
-    fn parse_line(line: &str) -> Vec<Pipe> {
-        line.chars().map(Pipe::parse).collect()
+    fn parse(lines: &str) -> Result<Self, ParseError> {
+        let grid: Grid<Pipe> = Grid::parse(lines)?;
+        let starts: Vec<Coord> = grid
+            .positions(|pipe| pipe == &Pipe::Start)
+            .take(2)
+            .collect();
+        let start = match (starts.first(), starts.get(1)) {
+            (None, _) => return Err(ParseError::new("the map has no `S` starting tile")),
+            (Some(_), Some((row, column))) => {
+                return Err(ParseError::at(
+                    row + 1,
+                    column + 1,
+                    "the map has a second `S` starting tile",
+                ))
+            }
+            (Some(start), None) => *start,
+        };
+        let map = Self { grid, start };
+        map.walk_loop()?;
+        Ok(map)
     }
 
-    fn count_all_inside_coords(&self) -> usize {
-        let path = self.path_points();
+    fn count_all_inside_coords(&self, path: &[Coord]) -> usize {
         let mut num_inside = 0;
-        for x in 0..self.row_count - 1 {
-            for y in 0..self.col_count - 1 {
-                if self.is_coord_in_path((x, y), path.clone()) {
+        for x in 0..self.grid.rows() - 1 {
+            for y in 0..self.grid.cols() - 1 {
+                if self.is_coord_in_path((x, y), path) {
                     num_inside += 1;
-                    println!("found coord in path ({x}, {y})");
                 }
             }
         }
@@ -127,12 +136,12 @@ impl Map {
         num_inside
     }
 
-    fn is_coord_in_path(&self, coord: Coord, path: Vec<Coord>) -> bool {
+    fn is_coord_in_path(&self, coord: Coord, path: &[Coord]) -> bool {
         let mut min_x = 0;
-        let mut max_x = self.row_count;
+        let mut max_x = self.grid.rows();
         let mut min_y = 0;
-        let mut max_y = self.col_count;
-        for p in &path {
+        let mut max_y = self.grid.cols();
+        for p in path {
             // quit early if on path, we only want inside
             if coord == *p {
                 return false;
@@ -150,8 +159,7 @@ impl Map {
         let mut inside = false;
         // prepend last so we can iterate once
         let mut new_path = vec![*path.last().unwrap()];
-        let mut path_copy = path.clone();
-        new_path.append(&mut path_copy);
+        new_path.extend_from_slice(path);
 
         // ray intersection algorithm I stole, adapted to exclude points on path above
         for window in new_path.windows(2) {
@@ -173,140 +181,66 @@ impl Map {
         inside
     }
 
-    fn path_points(&self) -> Vec<Coord> {
-        let start = self.find_starting_point();
-        let mut prev = start;
-        // pick any direction from start
-        let mut current = *self.find_possible_paths(start).first().unwrap();
-        let mut steps = vec![start, current];
-
-        while current != start {
-            let possible = self.find_possible_paths(current);
-            let next = possible.iter().find(|path| **path != prev).unwrap();
+    /// The tiles of the loop in order, starting from `S` in whichever direction comes first.
+    fn walk_loop(&self) -> Result<Vec<Coord>, ParseError> {
+        let broken = |(row, column): Coord, message| ParseError::at(row + 1, column + 1, message);
+        let mut prev = self.start;
+        let mut current = *self
+            .find_possible_paths(self.start)
+            .first()
+            .ok_or_else(|| {
+                broken(
+                    self.start,
+                    "the `S` starting tile doesn't connect to any pipe",
+                )
+            })?;
+        let mut path = vec![self.start];
+
+        while current != self.start {
+            path.push(current);
+            let next = self
+                .find_possible_paths(current)
+                .into_iter()
+                .find(|path| *path != prev)
+                .ok_or_else(|| broken(current, "the loop through `S` breaks off here"))?;
             prev = current;
-            current = *next;
-            steps.push(*next);
+            current = next;
         }
 
-        steps
-    }
-
-    fn steps_along_path(&self) -> usize {
-        let mut steps = 1;
-        let start = self.find_starting_point();
-        let mut prev = start;
-        // pick any direction from start
-        let mut current = *self.find_possible_paths(start).first().unwrap();
-
-        while current != start {
-            let possible = self.find_possible_paths(current);
-            println!("{steps} so far {prev:?} -> {current:?}: {possible:?}");
-            let next = possible.iter().find(|path| **path != prev).unwrap();
-            prev = current;
-            current = *next;
-            steps += 1;
-        }
-
-        steps
-    }
-
-    /// Find 0-indexed starting (row, column)
-    fn find_starting_point(&self) -> Coord {
-        for (i, row) in self.grid.iter().enumerate() {
-            for (j, pipe) in row.iter().enumerate() {
-                if pipe == &Pipe::Start {
-                    return (i, j);
-                }
-            }
-        }
-        panic!("couldn't find start");
+        Ok(path)
     }
 
     fn find_possible_paths(&self, coord: Coord) -> Vec<Coord> {
-        let pipe = self.get(coord);
-        //println!("{pipe:?}, {:?}", pipe.connects_to());
-        let possible_coords = pipe
-            .connects_to()
-            .iter()
+        self.grid
+            .neighbours4(coord)
             // check if pipes actually connect
-            .filter(|d| self.pipes_connect(coord, **d))
-            .map(|d| self.coordinate_in_direction(coord, *d).unwrap())
-            .collect();
-
-        possible_coords
+            .filter(|next| self.pipes_connect(coord, *next))
+            .collect()
     }
 
-    fn coordinate_in_direction(&self, coord: Coord, direction: Direction) -> Option<Coord> {
-        match direction {
-            Direction::West => {
-                if coord.1 == 0 {
-                    None
-                } else {
-                    Some((coord.0, coord.1 - 1))
-                }
-            }
-            Direction::East => {
-                if coord.1 == self.col_count - 1 {
-                    None
-                } else {
-                    Some((coord.0, coord.1 + 1))
-                }
-            }
-            Direction::North => {
-                if coord.0 == 0 {
-                    None
-                } else {
-                    Some((coord.0 - 1, coord.1))
-                }
-            }
-            Direction::South => {
-                if coord.0 == self.row_count - 1 {
-                    None
-                } else {
-                    Some((coord.0 + 1, coord.1))
-                }
-            }
-        }
-    }
-
-    fn pipes_connect(&self, coord: Coord, direction: Direction) -> bool {
-        let start_pipe = self.get(coord);
-        let end_pipe = match self.coordinate_in_direction(coord, direction) {
-            Some(pipe) => self.get(pipe),
-            None => return false,
-        };
-        let start_directions: Vec<Direction> = start_pipe.connects_to();
-
-        if !start_directions.contains(&direction) {
-            return false;
-        }
-
-        let end_directions: Vec<Direction> = end_pipe
+    fn opens_towards(&self, from: Coord, to: Coord) -> bool {
+        self.grid[from]
             .connects_to()
             .iter()
-            .map(|d| d.opposite())
-            .collect();
-        //println!("start: {start_directions:?} - end: {end_directions:?}");
-
-        end_directions.contains(&direction)
+            .any(|d| UPoint::from(from).step(*d, self.grid.bounds()) == Some(to.into()))
     }
 
-    fn get(&self, coord: Coord) -> Pipe {
-        self.grid[coord.0][coord.1]
+    /// Adjacent pipes connect if each has an opening facing the other.
+    fn pipes_connect(&self, a: Coord, b: Coord) -> bool {
+        self.opens_towards(a, b) && self.opens_towards(b, a)
     }
 }
 
-type Coord = (usize, usize);
-
 #[cfg(test)]
 mod tests {
-    use crate::day10::{answer_part_1, answer_part_2, get_input_string, Map};
+    use crate::aoc2023::day10::{answer_part_1, answer_part_2, Map};
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 10, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 6951);
-        assert_eq!(answer_part_2(lines), 563);
+        assert_eq!(answer_part_1(lines), Ok(6951));
+        assert_eq!(answer_part_2(lines), Ok(563));
     }
 
     const SAMPLE_INPUT: &str = r#"..F7.
@@ -317,22 +251,22 @@ LJ...
 "#;
     #[test]
     fn test_find_possible_paths() {
-        let map = Map::parse(SAMPLE_INPUT);
+        let map = Map::parse(SAMPLE_INPUT).unwrap();
         //assert_eq!(map.find_possible_paths((2, 1)), vec![(1, 1), (2, 0)]);
         assert_eq!(map.find_possible_paths((4, 0)), vec![(3, 0), (4, 1)]);
     }
 
     #[test]
     fn test_pipes_connect() {
-        let map = Map::parse(SAMPLE_INPUT);
-        assert!(map.pipes_connect((2, 1), crate::day10::Direction::North));
-        assert!(!map.pipes_connect((2, 1), crate::day10::Direction::South));
-        assert!(map.pipes_connect((4, 0), crate::day10::Direction::East));
+        let map = Map::parse(SAMPLE_INPUT).unwrap();
+        assert!(map.pipes_connect((2, 1), (1, 1)));
+        assert!(!map.pipes_connect((2, 1), (3, 1)));
+        assert!(map.pipes_connect((4, 0), (4, 1)));
     }
 
     #[test]
     fn test_pipe_maze_distance() {
-        assert_eq!(answer_part_1(SAMPLE_INPUT), 8);
+        assert_eq!(answer_part_1(SAMPLE_INPUT), Ok(8));
     }
 
     const SAMPLE_INPUT_2: &str = r#"...........
@@ -347,6 +281,30 @@ LJ...
 
     #[test]
     fn test_contained() {
-        assert_eq!(answer_part_2(SAMPLE_INPUT_2), 4);
+        assert_eq!(answer_part_2(SAMPLE_INPUT_2), Ok(4));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Map::parse("..F7.\n.FJ|.\nSJ.L7\n|F-+J")
+                .err()
+                .unwrap()
+                .to_string(),
+            "line 4, column 4: weird map tile `+`, expected one of `|-LJ7F.S`"
+        );
+        assert!(Map::parse("F7\nLJ").is_err());
+        assert_eq!(
+            Map::parse("S7\nLS").err().unwrap().to_string(),
+            "line 2, column 2: the map has a second `S` starting tile"
+        );
+        assert_eq!(
+            answer_part_1("S.").err().unwrap().to_string(),
+            "line 1, column 1: the `S` starting tile doesn't connect to any pipe"
+        );
+        assert_eq!(
+            Map::parse("S-7\n|.|\nL-.").err().unwrap().to_string(),
+            "line 3, column 2: the loop through `S` breaks off here"
+        );
     }
 }
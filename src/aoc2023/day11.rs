@@ -1,25 +1,41 @@
 use itertools::Itertools;
 //use rayon::prelude::*;
+use crate::{answer::Answer, geometry::UPoint, grid::Grid, parse::ParseError, solution::Solution};
 
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+pub(crate) struct Day11;
+
+impl Solution for Day11 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        11
+    }
+
+    fn title(&self) -> &'static str {
+        "Cosmic Expansion"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
+    }
 }
 
-fn answer_part_1(lines: &str) -> usize {
-    let mut map = Map::parse(lines);
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    let mut map = Map::parse(lines)?;
     map.expand_universe(1);
-    map.all_galaxy_distances()
+    Ok(map.all_galaxy_distances())
 }
 
-fn answer_part_2(lines: &str) -> usize {
-    let map = Map::parse(lines);
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    let map = Map::parse(lines)?;
     let expanded = map.expanded_universe(1_000_000);
-    expanded.all_galaxy_distances()
-}
-
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day11.txt")
+    Ok(expanded.all_galaxy_distances())
 }
 
 #[derive(Clone, PartialEq, Debug, Copy)]
@@ -28,12 +44,16 @@ enum Space {
     Empty,
 }
 
-impl Space {
-    fn parse(space: char) -> Self {
+impl TryFrom<char> for Space {
+    type Error = ParseError;
+
+    fn try_from(space: char) -> Result<Self, Self::Error> {
         match space {
-            '.' => Space::Empty,
-            '#' => Space::Galaxy,
-            _ => panic!("unallowed space item"),
+            '.' => Ok(Space::Empty),
+            '#' => Ok(Space::Galaxy),
+            _ => Err(ParseError::new(format!(
+                "unallowed space item `{space}`, expected `.` or `#`"
+            ))),
         }
     }
 }
@@ -51,7 +71,7 @@ struct ExpandedUniverse {
     expansion_multiplier: usize,
     empty_columns: Vec<usize>,
     empty_rows: Vec<usize>,
-    galaxy_locations: Vec<Point>,
+    galaxy_locations: Vec<UPoint>,
 }
 
 impl ExpandedUniverse {
@@ -61,25 +81,23 @@ impl ExpandedUniverse {
             .combinations(2)
             .map(|points| {
                 // how many times we cross empty space?
-                let a = points[0].clone();
-                let b = points[1].clone();
-                self.expanded_distance(a, b)
+                self.expanded_distance(*points[0], *points[1])
             })
             .sum()
     }
 
-    fn expanded_distance(&self, a: Point, b: Point) -> usize {
-        let empty_rows_crossed = create_range(a.x, b.x)
+    fn expanded_distance(&self, a: UPoint, b: UPoint) -> usize {
+        let empty_rows_crossed = create_range(a.y, b.y)
             .filter(|row_index| self.empty_rows.contains(row_index))
             .count();
-        let empty_columns_crossed = create_range(a.y, b.y)
+        let empty_columns_crossed = create_range(a.x, b.x)
             .filter(|col_index| self.empty_columns.contains(col_index))
             .count();
         let row_expansion =
             (empty_rows_crossed * self.expansion_multiplier).saturating_sub(empty_rows_crossed);
         let col_expansion = (empty_columns_crossed * self.expansion_multiplier)
             .saturating_sub(empty_columns_crossed);
-        a.distance(b.clone()) + row_expansion + col_expansion
+        a.manhattan(b) + row_expansion + col_expansion
     }
 }
 
@@ -93,175 +111,95 @@ fn create_range(a: usize, b: usize) -> std::ops::RangeInclusive<usize> {
 }
 
 struct Map {
-    grid: Vec<Vec<Space>>,
-    height: usize,
-    width: usize,
+    grid: Grid<Space>,
 }
 
 impl Map {
-    fn parse(lines: &str) -> Self {
-        let grid: Vec<_> = lines.lines().map(Map::parse_line).collect();
-        let height = grid.len();
-        let width = grid.first().unwrap().len();
-        Self {
-            grid,
-            height,
-            width,
-        }
+    fn parse(lines: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            grid: Grid::parse(lines)?,
+        })
     }
 
-    fn parse_line(line: &str) -> Vec<Space> {
-        line.chars().map(Space::parse).collect()
+    fn empty_rows(&self) -> Vec<usize> {
+        (0..self.grid.rows())
+            .filter(|row| {
+                self.grid
+                    .row(*row)
+                    .iter()
+                    .all(|space| *space == Space::Empty)
+            })
+            .collect()
     }
 
-    fn expanded_universe(&self, multiplier: usize) -> ExpandedUniverse {
-        let empty_rows: Vec<usize> = self
-            .grid
-            .iter()
-            .enumerate()
-            .filter(|(_, line)| line.iter().all(|space| *space == Space::Empty))
-            .map(|(i, _)| i)
-            .collect();
-        let mut empty_columns = vec![];
-        for i in 0..self.width {
-            let mut all_empty = true;
-            for j in 0..self.height {
-                if self.get(j, i) != Space::Empty {
-                    all_empty = false;
-                }
-            }
-
-            if all_empty {
-                empty_columns.push(i);
-            }
-        }
+    fn empty_columns(&self) -> Vec<usize> {
+        (0..self.grid.cols())
+            .filter(|col| self.grid.column(*col).all(|space| *space == Space::Empty))
+            .collect()
+    }
 
+    fn expanded_universe(&self, multiplier: usize) -> ExpandedUniverse {
         ExpandedUniverse {
             expansion_multiplier: multiplier,
-            empty_rows,
-            empty_columns,
+            empty_rows: self.empty_rows(),
+            empty_columns: self.empty_columns(),
             galaxy_locations: self.find_all_galaxies(),
         }
     }
 
     fn expand_universe(&mut self, expansion: usize) {
-        let empty_rows: Vec<usize> = self
-            .grid
-            .iter()
-            .enumerate()
-            .filter(|(_, line)| line.iter().all(|space| *space == Space::Empty))
-            .map(|(i, _)| i)
-            .collect();
-
-        let new_row: Vec<Space> = std::iter::repeat(Space::Empty).take(self.width).collect();
-
         let mut offset = 0;
-        for i in empty_rows {
+        for i in self.empty_rows() {
             for _n in 0..expansion {
-                self.grid.insert(i + offset, new_row.clone());
-                self.height += 1;
+                let new_row = vec![Space::Empty; self.grid.cols()];
+                self.grid.insert_row(i + offset, new_row);
                 offset += 1;
             }
         }
 
-        let mut empty_column_ids = vec![];
-        for i in 0..self.width {
-            let mut all_empty = true;
-            for j in 0..self.height {
-                if self.get(j, i) != Space::Empty {
-                    all_empty = false;
-                }
-            }
-
-            if all_empty {
-                empty_column_ids.push(i);
-            }
-        }
-
         offset = 0;
-        for i in empty_column_ids {
+        for i in self.empty_columns() {
             for _n in 0..expansion {
-                for j in 0..self.height {
-                    self.grid[j].insert(i + offset, Space::Empty);
-                    // need to increment as we insert
-                }
-                self.width += 1;
+                let new_column = vec![Space::Empty; self.grid.rows()];
+                self.grid.insert_col(i + offset, new_column);
                 offset += 1;
             }
         }
     }
 
-    fn find_all_galaxies(&self) -> Vec<Point> {
-        let mut galaxy_locations = vec![];
-        for x in 0..self.height {
-            for y in 0..self.width {
-                if self.get(x, y) == Space::Galaxy {
-                    galaxy_locations.push(Point { x, y });
-                }
-            }
-        }
-
-        galaxy_locations
+    fn find_all_galaxies(&self) -> Vec<UPoint> {
+        self.grid
+            .positions(|space| *space == Space::Galaxy)
+            .map(UPoint::from)
+            .collect()
     }
 
     fn all_galaxy_distances(&self) -> usize {
         self.find_all_galaxies()
             .iter()
             .combinations(2)
-            .map(|points| points[0].distance(points[1].clone()))
+            .map(|points| points[0].manhattan(*points[1]))
             .sum()
     }
-
-    fn get(&self, x: usize, y: usize) -> Space {
-        self.grid[x][y]
-    }
 }
 
 impl std::fmt::Display for Map {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let map = self
-            .grid
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<_>>()
-                    .join("")
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-        write!(f, "{map}")
-    }
-}
-
-#[derive(Clone, PartialEq, Debug)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-impl Point {
-    fn distance(&self, other: Point) -> usize {
-        ((self.x as isize - other.x as isize).abs() + (self.y as isize - other.y as isize).abs())
-            as usize
-    }
-}
-
-impl std::fmt::Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+        write!(f, "{}", self.grid)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day11::{answer_part_1, answer_part_2, get_input_string, Map, Point};
+    use crate::aoc2023::day11::{answer_part_1, answer_part_2, Map};
+    use crate::geometry::UPoint;
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 11, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 9957702);
-        assert_eq!(answer_part_2(lines), 512240933238);
+        assert_eq!(answer_part_1(lines), Ok(9957702));
+        assert_eq!(answer_part_2(lines), Ok(512240933238));
     }
 
     const SAMPLE_INPUT: &str = r"...#......
@@ -277,7 +215,7 @@ mod tests {
 
     #[test]
     fn test_universe_expansion() {
-        let mut map = Map::parse(SAMPLE_INPUT);
+        let mut map = Map::parse(SAMPLE_INPUT).unwrap();
         map.expand_universe(1);
         println!("{map}");
         assert_eq!(
@@ -300,15 +238,15 @@ mod tests {
     #[test]
     fn test_shortest_paths() {
         let paths_sum = answer_part_1(SAMPLE_INPUT);
-        assert_eq!(paths_sum, 374);
-        let map = Map::parse(SAMPLE_INPUT);
+        assert_eq!(paths_sum, Ok(374));
+        let map = Map::parse(SAMPLE_INPUT).unwrap();
         let expanded = map.expanded_universe(2);
         assert_eq!(expanded.all_galaxy_distances(), 374);
     }
 
     #[test]
     fn test_shortest_paths_expand_10() {
-        let mut map = Map::parse(SAMPLE_INPUT);
+        let mut map = Map::parse(SAMPLE_INPUT).unwrap();
         let expanded = map.expanded_universe(10);
         map.expand_universe(9);
         println!("{map}");
@@ -318,14 +256,14 @@ mod tests {
 
     #[test]
     fn test_point_distance() {
-        let g1 = Point { x: 0, y: 4 };
-        let g3 = Point { x: 2, y: 0 };
-        let g6 = Point { x: 7, y: 12 };
-        let g7 = Point { x: 10, y: 9 };
-        let g8 = Point { x: 11, y: 0 };
-        let g9 = Point { x: 11, y: 5 };
-        assert_eq!(g1.distance(g7), 15);
-        assert_eq!(g3.distance(g6), 17);
-        assert_eq!(g8.distance(g9), 5);
+        let g1 = UPoint { x: 4, y: 0 };
+        let g3 = UPoint { x: 0, y: 2 };
+        let g6 = UPoint { x: 12, y: 7 };
+        let g7 = UPoint { x: 9, y: 10 };
+        let g8 = UPoint { x: 0, y: 11 };
+        let g9 = UPoint { x: 5, y: 11 };
+        assert_eq!(g1.manhattan(g7), 15);
+        assert_eq!(g3.manhattan(g6), 17);
+        assert_eq!(g8.manhattan(g9), 5);
     }
 }
@@ -1,38 +1,53 @@
-use itertools::Itertools;
+use crate::{
+    answer::Answer,
+    parse::{self, ParseError},
+    solution::Solution,
+};
 use rayon::prelude::*;
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
-}
+use std::collections::HashMap;
+pub(crate) struct Day12;
 
-fn answer_part_1(lines: &str) -> usize {
-    parse_all_condition_reports(lines)
-        .par_iter()
-        .map(|r| r.valid_condition_count(&r.condition, &r.criteria))
-        .sum()
-}
+impl Solution for Day12 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        12
+    }
+
+    fn title(&self) -> &'static str {
+        "Hot Springs"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
 
-fn answer_part_2(lines: &str) -> usize {
-    let reports = parse_all_condition_reports(lines);
-    let iter = reports.iter();
-    let mut total = 0;
-    let mut n = 1;
-    for report in iter {
-        let count = report.v3_valid_condition_count();
-        total += count;
-        println!("finished #{} - {}", n, total);
-        n += 1;
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
     }
+}
 
-    total
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    Ok(parse_all_condition_reports(lines)?
+        .par_iter()
+        .map(|r| r.valid_condition_count(&r.condition, &r.criteria))
+        .sum())
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day12.txt")
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    Ok(parse_all_condition_reports(lines)?
+        .par_iter()
+        .map(|r| {
+            let unfolded = r.unfolded(5);
+            unfolded.valid_condition_count(&unfolded.condition, &unfolded.criteria)
+        })
+        .sum())
 }
 
-fn parse_all_condition_reports(lines: &str) -> Vec<ConditionReport> {
-    lines.lines().map(ConditionReport::parse).collect()
+fn parse_all_condition_reports(lines: &str) -> Result<Vec<ConditionReport>, ParseError> {
+    parse::lines(lines, ConditionReport::parse)
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -42,13 +57,17 @@ enum SpringCondition {
     Unknown,
 }
 
-impl SpringCondition {
-    fn parse(item: char) -> Self {
+impl TryFrom<char> for SpringCondition {
+    type Error = ParseError;
+
+    fn try_from(item: char) -> Result<Self, Self::Error> {
         match item {
-            '.' => SpringCondition::Healthy,
-            '#' => SpringCondition::Damaged,
-            '?' => SpringCondition::Unknown,
-            _ => panic!("Unknown spring condition"),
+            '.' => Ok(SpringCondition::Healthy),
+            '#' => Ok(SpringCondition::Damaged),
+            '?' => Ok(SpringCondition::Unknown),
+            _ => Err(ParseError::new(format!(
+                "unknown spring condition `{item}`, expected `.`, `#` or `?`"
+            ))),
         }
     }
 }
@@ -59,17 +78,17 @@ struct Condition {
 }
 
 impl Condition {
-    fn parse(line: &str) -> Self {
-        let springs = line.chars().map(SpringCondition::parse).collect();
-        Self { springs }
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let springs = parse::chars(line)?;
+        Ok(Self { springs })
     }
 
-    #[allow(dead_code)]
-    fn repeated_version(&self) -> Self {
-        let mut springs = self.springs.clone();
-        springs.push(SpringCondition::Unknown);
-        springs.append(&mut self.springs.clone());
-        Self { springs }
+    /// `times` copies of the springs with an unknown spring between each.
+    fn repeated_version(&self, times: usize) -> Self {
+        let copies = vec![self.springs.as_slice(); times];
+        Self {
+            springs: copies.join(&SpringCondition::Unknown),
+        }
     }
 }
 
@@ -79,157 +98,106 @@ struct ConditionReport {
 }
 
 impl ConditionReport {
-    fn parse(line: &str) -> Self {
-        let (condition_string, criteria_string) = line.split_once(' ').unwrap();
-        let condition = Condition::parse(condition_string);
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let (condition_string, criteria_string) = line.split_once(' ').ok_or_else(|| {
+            ParseError::at(
+                1,
+                line.chars().count() + 1,
+                "expected springs followed by a space and group sizes",
+            )
+        })?;
+        let condition = Condition::parse(condition_string)?;
+        let mut column = condition_string.chars().count() + 2;
         let criteria = criteria_string
             .split(',')
-            .map(|n| n.parse().unwrap())
-            .collect();
-        Self {
+            .map(|n| {
+                let size = parse::number(n).map_err(|e| e.within(1, column));
+                column += n.chars().count() + 1;
+                size
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
             condition,
             criteria,
-        }
-    }
-
-    fn check_condition_fits_criteria(&self, condition: &Condition, criteria: &Vec<usize>) -> bool {
-        let mut last_was_damaged = false;
-        let mut running_count = 0;
-        let mut found_criteria = vec![];
-        for c in &condition.springs {
-            if c == &SpringCondition::Damaged {
-                last_was_damaged = true;
-                running_count += 1;
-            } else {
-                if last_was_damaged {
-                    found_criteria.push(running_count);
-                    running_count = 0;
-                }
-                last_was_damaged = false;
-            }
-        }
-        if last_was_damaged {
-            found_criteria.push(running_count);
-        }
-
-        &found_criteria == criteria
+        })
     }
 
-    fn valid_condition_count(&self, base_condition: &Condition, criteria: &Vec<usize>) -> usize {
-        let unknown_indices: Vec<_> = base_condition
-            .springs
-            .iter()
-            .enumerate()
-            .filter(|(_, c)| *c == &SpringCondition::Unknown)
-            .map(|(i, _)| i)
-            .collect();
-        let num_unknown_indices = unknown_indices.len();
-        //println!("unknown indices: {num_unknown_indices}");
-
-        let mut possible_condition_count = 0;
-        let possible_unknown_states = itertools::repeat_n(
-            [SpringCondition::Healthy, SpringCondition::Damaged].iter(),
-            num_unknown_indices,
-        )
-        .multi_cartesian_product();
-        for possible in possible_unknown_states {
-            //println!("{possible:?}");
-            let mut condition = base_condition.clone();
-            for (p, i) in possible.iter().zip(unknown_indices.clone()) {
-                condition.springs[i] = **p;
-            }
-            //println!("{condition:?}");
-            if self.check_condition_fits_criteria(&condition, criteria) {
-                possible_condition_count += 1;
-            }
+    /// The report as it reads once unfolded: the springs `times` over, joined by unknown
+    /// springs, with the group sizes repeated to match.
+    fn unfolded(&self, times: usize) -> Self {
+        Self {
+            condition: self.condition.repeated_version(times),
+            criteria: self.criteria.repeat(times),
         }
-
-        possible_condition_count
     }
 
-    fn valid_conditions(
-        &self,
-        base_condition: &Condition,
-        criteria: &Vec<usize>,
-    ) -> Vec<Condition> {
-        let unknown_indices: Vec<_> = base_condition
-            .springs
-            .iter()
-            .enumerate()
-            .filter(|(_, c)| *c == &SpringCondition::Unknown)
-            .map(|(i, _)| i)
-            .collect();
-        let num_unknown_indices = unknown_indices.len();
-        //println!("unknown indices: {num_unknown_indices}");
-
-        let mut possible_conditions = vec![];
-        let possible_unknown_states = itertools::repeat_n(
-            [SpringCondition::Healthy, SpringCondition::Damaged].iter(),
-            num_unknown_indices,
-        )
-        .multi_cartesian_product();
-        for possible in possible_unknown_states {
-            //println!("{possible:?}");
-            let mut condition = base_condition.clone();
-            for (p, i) in possible.iter().zip(unknown_indices.clone()) {
-                condition.springs[i] = **p;
-            }
-            //println!("{condition:?}");
-            if self.check_condition_fits_criteria(&condition, criteria) {
-                possible_conditions.push(condition.clone());
-            }
+    fn valid_condition_count(&self, condition: &Condition, criteria: &[usize]) -> usize {
+        ArrangementCounter {
+            springs: &condition.springs,
+            criteria,
+            memo: HashMap::new(),
         }
-
-        possible_conditions
-    }
-
-    #[allow(dead_code)]
-    fn v2_valid_condition_count(&self) -> usize {
-        let base_count = self.valid_condition_count(&self.condition, &self.criteria);
-        let mut repeat_criteria = self.criteria.clone();
-        repeat_criteria.append(&mut self.criteria.clone());
-        let expanded_count =
-            self.valid_condition_count(&self.condition.repeated_version(), &repeat_criteria);
-        base_count * (expanded_count / base_count).pow(4)
+        .count(0, 0, 0)
     }
+}
 
-    fn v3_valid_condition_count(&self) -> usize {
-        let base_count = self.valid_condition_count(&self.condition, &self.criteria);
-        let _last_spring = *self.condition.springs.last().unwrap();
-        let mut end_with_unknown = self.condition.springs.clone();
-        end_with_unknown.push(SpringCondition::Unknown);
-        let end = Condition {
-            springs: end_with_unknown,
-        };
-        let end_count = self.valid_conditions(&end, &self.criteria).len();
+/// Counts the ways to fill in unknown springs so the damaged runs match the criteria, walking
+/// the springs left to right and remembering the count from each state it reaches.
+struct ArrangementCounter<'a> {
+    springs: &'a [SpringCondition],
+    criteria: &'a [usize],
+    memo: HashMap<(usize, usize, usize), usize>,
+}
 
-        let mut original_springs = self.condition.springs.clone();
-        let mut start_with_unknown = vec![SpringCondition::Unknown];
-        start_with_unknown.append(&mut original_springs);
-        let start = Condition {
-            springs: start_with_unknown,
+impl ArrangementCounter<'_> {
+    /// Arrangements of the springs from index `spring` onwards, given that the groups before
+    /// `group` are complete and the springs just before this one form a damaged run of
+    /// length `run` towards `group`.
+    fn count(&mut self, spring: usize, group: usize, run: usize) -> usize {
+        let Some(&condition) = self.springs.get(spring) else {
+            // the last run has to close the last group, or there must be none left
+            return if run == 0 {
+                usize::from(group == self.criteria.len())
+            } else {
+                usize::from(group + 1 == self.criteria.len() && run == self.criteria[group])
+            };
         };
-        let conditions = self.valid_conditions(&start, &self.criteria);
-        let start_count = conditions.len();
+        if let Some(&count) = self.memo.get(&(spring, group, run)) {
+            return count;
+        }
 
-        let expanded_count = std::cmp::max(start_count, end_count);
+        let mut count = 0;
+        let wanted = self.criteria.get(group).copied();
+        if condition != SpringCondition::Healthy && wanted.is_some_and(|size| run < size) {
+            // extend the current run, or start the next one
+            count += self.count(spring + 1, group, run + 1);
+        }
+        if condition != SpringCondition::Damaged {
+            if run == 0 {
+                count += self.count(spring + 1, group, 0);
+            } else if wanted == Some(run) {
+                // a healthy spring closes a run of exactly the right size
+                count += self.count(spring + 1, group + 1, 0);
+            }
+        }
 
-        base_count * (expanded_count).pow(4)
+        self.memo.insert((spring, group, run), count);
+        count
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day12::{
-        answer_part_1, answer_part_2, get_input_string, parse_all_condition_reports, Condition,
-        ConditionReport,
+    use crate::aoc2023::day12::{
+        answer_part_1, answer_part_2, parse_all_condition_reports, Condition, ConditionReport,
     };
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 12, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 7195);
-        //assert_eq!(answer_part_2(lines), 3497545717240); // too low!
+        assert_eq!(answer_part_1(lines), Ok(7195));
+        assert_eq!(answer_part_2(lines), Ok(33992866292225));
     }
 
     const SAMPLE_INPUT: &str = r#"???.### 1,1,3
@@ -241,99 +209,137 @@ mod tests {
 
     #[test]
     fn test_spring_arrangements() {
-        assert_eq!(answer_part_1(SAMPLE_INPUT), 21);
-        assert_eq!(answer_part_2(SAMPLE_INPUT), 525232);
+        assert_eq!(answer_part_1(SAMPLE_INPUT), Ok(21));
+        assert_eq!(answer_part_2(SAMPLE_INPUT), Ok(525152));
     }
 
     #[test]
     fn test_check_condition_report_match() {
-        let condition_reports = parse_all_condition_reports(SAMPLE_INPUT);
+        let condition_reports = parse_all_condition_reports(SAMPLE_INPUT).unwrap();
         let first_report = condition_reports.first().unwrap();
-        let check_condition = Condition::parse("#.#.###");
-        assert!(
-            first_report.check_condition_fits_criteria(&check_condition, &first_report.criteria)
+        let check_condition = Condition::parse("#.#.###").unwrap();
+        assert_eq!(
+            first_report.valid_condition_count(&check_condition, &first_report.criteria),
+            1
+        );
+        let wrong_condition = Condition::parse("#.#.##.").unwrap();
+        assert_eq!(
+            first_report.valid_condition_count(&wrong_condition, &first_report.criteria),
+            0
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_all_condition_reports("???.### 1,1,3\n.?x.### 1,1,3")
+            .err()
+            .unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(
+            err.message,
+            "unknown spring condition `x`, expected `.`, `#` or `?`"
+        );
+
+        let err = ConditionReport::parse("???.### 1,x,3").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 11));
+        assert_eq!(err.message, "expected a number, found `x`");
+        assert!(ConditionReport::parse("???.###").is_err());
+    }
+
+    fn unfolded_count(report: &ConditionReport) -> usize {
+        let unfolded = report.unfolded(5);
+        unfolded.valid_condition_count(&unfolded.condition, &unfolded.criteria)
+    }
+
+    #[test]
+    fn test_repeated_version() {
+        let condition = Condition::parse(".#").unwrap();
+        assert_eq!(condition.repeated_version(1), condition);
+        assert_eq!(
+            condition.repeated_version(3),
+            Condition::parse(".#?.#?.#").unwrap()
+        );
+        let report = ConditionReport::parse(".# 1").unwrap().unfolded(5);
+        assert_eq!(
+            report.condition,
+            Condition::parse(".#?.#?.#?.#?.#").unwrap()
         );
+        assert_eq!(report.criteria, [1, 1, 1, 1, 1]);
     }
 
     #[test]
     fn test_expanded_combo_count() {
-        let condition_reports = parse_all_condition_reports(SAMPLE_INPUT);
+        let condition_reports = parse_all_condition_reports(SAMPLE_INPUT).unwrap();
         let mut report_iter = condition_reports.iter();
         let report = report_iter.next().unwrap();
         assert_eq!(
             report.valid_condition_count(&report.condition, &report.criteria),
             1
         );
-        assert_eq!(report.v2_valid_condition_count(), 1);
-        //assert_eq!(report.v3_valid_condition_count(), 1);
+        assert_eq!(unfolded_count(report), 1);
 
         let report = report_iter.next().unwrap();
         assert_eq!(
             report.valid_condition_count(&report.condition, &report.criteria),
             4
         );
-        assert_eq!(report.v2_valid_condition_count(), 16384);
-        assert_eq!(report.v3_valid_condition_count(), 16384);
+        assert_eq!(unfolded_count(report), 16384);
 
         let report = report_iter.next().unwrap();
         assert_eq!(
             report.valid_condition_count(&report.condition, &report.criteria),
             1
         );
-        assert_eq!(report.v2_valid_condition_count(), 1);
-        assert_eq!(report.v3_valid_condition_count(), 1);
+        assert_eq!(unfolded_count(report), 1);
 
         let report = report_iter.next().unwrap();
         assert_eq!(
             report.valid_condition_count(&report.condition, &report.criteria),
             1
         );
-        assert_eq!(report.v2_valid_condition_count(), 16);
-        assert_eq!(report.v3_valid_condition_count(), 16);
+        assert_eq!(unfolded_count(report), 16);
 
         let report = report_iter.next().unwrap();
         assert_eq!(
             report.valid_condition_count(&report.condition, &report.criteria),
             4
         );
-        assert_eq!(report.v2_valid_condition_count(), 2500);
-        assert_eq!(report.v3_valid_condition_count(), 2500);
+        assert_eq!(unfolded_count(report), 2500);
 
         let report = report_iter.next().unwrap();
         assert_eq!(
             report.valid_condition_count(&report.condition, &report.criteria),
             10
         );
-        assert_eq!(report.v2_valid_condition_count(), 506250);
-        assert_eq!(report.v3_valid_condition_count(), 506250);
+        assert_eq!(unfolded_count(report), 506250);
     }
 
     #[test]
     fn test_shifted_case() {
         //trying to discover the fucking relation between these patterns
-        let cr = ConditionReport::parse(".??..??...?##. 1,1,3");
+        let cr = ConditionReport::parse(".??..??...?##. 1,1,3").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 4);
-        let cr = ConditionReport::parse("???.### 1,1,3");
+        let cr = ConditionReport::parse("???.### 1,1,3").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 1);
-        let cr = ConditionReport::parse("???.###????.### 1,1,3,1,1,3");
+        let cr = ConditionReport::parse("???.###????.### 1,1,3,1,1,3").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 1);
-        let cr = ConditionReport::parse("###????.### 3,1,1,3");
+        let cr = ConditionReport::parse("###????.### 3,1,1,3").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 1);
-        let cr = ConditionReport::parse("?###??????????###???????? 3,2,1,3,2,1");
+        let cr = ConditionReport::parse("?###??????????###???????? 3,2,1,3,2,1").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 150);
-        let cr = ConditionReport::parse("?###????????? 3,2,1");
+        let cr = ConditionReport::parse("?###????????? 3,2,1").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 15);
-        let cr = ConditionReport::parse("??###???????? 3,2,1");
+        let cr = ConditionReport::parse("??###???????? 3,2,1").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 10);
-        let cr = ConditionReport::parse(".??..??...?##.? 1,1,3");
+        let cr = ConditionReport::parse(".??..??...?##.? 1,1,3").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 4);
-        let cr = ConditionReport::parse("?.??..??...?##. 1,1,3");
+        let cr = ConditionReport::parse("?.??..??...?##. 1,1,3").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 8);
-        let cr = ConditionReport::parse("?????????.??????? 4,1,1,1,1");
+        let cr = ConditionReport::parse("?????????.??????? 4,1,1,1,1").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 166);
-        let cr = ConditionReport::parse("?????????.???????? 4,1,1,1,1");
+        let cr = ConditionReport::parse("?????????.???????? 4,1,1,1,1").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 314);
-        let cr = ConditionReport::parse("??????????.??????? 4,1,1,1,1");
+        let cr = ConditionReport::parse("??????????.??????? 4,1,1,1,1").unwrap();
         assert_eq!(cr.valid_condition_count(&cr.condition, &cr.criteria), 314);
     }
 }
@@ -1,202 +1,133 @@
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
-}
+use crate::{answer::Answer, grid::Grid, parse::ParseError, solution::Solution};
 
-fn answer_part_1(lines: &str) -> usize {
-    AllMaps::parse(lines).sum_values(false)
-}
+pub(crate) struct Day13;
 
-fn answer_part_2(lines: &str) -> usize {
-    AllMaps::parse(lines).sum_values(true)
-}
+impl Solution for Day13 {
+    fn year(&self) -> u16 {
+        2023
+    }
 
-struct Map {
-    rows: Vec<String>,
-    cols: Vec<String>,
-}
+    fn day(&self) -> usize {
+        13
+    }
 
-impl Map {
-    fn from_rows(rows: Vec<String>) -> Self {
-        let mut rows_iter = rows.iter();
-        let first_row = rows_iter.next().unwrap();
-        let mut cols: Vec<String> = first_row.chars().map(|c| c.to_string()).collect();
-        for row in rows_iter {
-            for (i, c) in row.chars().enumerate() {
-                cols[i].push(c);
-            }
-        }
-        Self { rows, cols }
+    fn title(&self) -> &'static str {
+        "Point of Incidence"
     }
 
-    fn find_value(&self, v2: bool) -> usize {
-        if let Some(value) = self.horizontal_mirror_value(v2) {
-            value
-        } else if let Some(value) = self.vertical_mirror_value(v2) {
-            value
-        } else {
-            println!("Couldn't horizontal or vertical mirror!");
-            for r in &self.rows {
-                println!("{r}");
-            }
-            0
-        }
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
     }
 
-    fn horizontal_mirror_value(&self, v2: bool) -> Option<usize> {
-        let row_count = self.rows.len();
-        for row in 0..row_count - 1 {
-            let a = &self.rows[row];
-            let b = &self.rows[row + 1];
-            if a == b {
-                if self.is_valid_horizontal_mirror(row, v2, false) {
-                    return Some((row + 1) * 100);
-                }
-            } else if v2 {
-                let distance = levenshtein::levenshtein(a, b);
-                if distance <= 1 && self.is_valid_horizontal_mirror(row, v2, distance == 1) {
-                    return Some((row + 1) * 100);
-                }
-            }
-        }
-        None
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
     }
+}
 
-    fn is_valid_horizontal_mirror(&self, start: usize, v2: bool, was_smudged: bool) -> bool {
-        let mut was_smudged = was_smudged;
-        let mut a = start as isize - 1;
-        let mut b = start + 2;
-        while a >= 0 && b < self.rows.len() {
-            let row_a = &self.rows[a as usize];
-            let row_b = &self.rows[b];
-            if !v2 && row_a != row_b {
-                return false;
-            } else if v2 {
-                let distance = levenshtein::levenshtein(row_a, row_b);
-                match distance.cmp(&1) {
-                    std::cmp::Ordering::Less => (),
-                    std::cmp::Ordering::Equal => {
-                        // one smudge ok, two smudge get outta here
-                        // wasn't true in practice, but oh well
-                        if was_smudged {
-                            return false;
-                        }
-                        was_smudged = true;
-                    }
-                    // too many smudges
-                    std::cmp::Ordering::Greater => return false,
-                }
-            }
-            a -= 1;
-            b += 1;
-        }
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    Ok(AllMaps::parse(lines)?.sum_values(0))
+}
 
-        if v2 {
-            // if it wasn't smudged, we don't want it
-            was_smudged
-        } else {
-            true
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    Ok(AllMaps::parse(lines)?.sum_values(1))
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Terrain {
+    Ash,
+    Rock,
+}
+
+impl TryFrom<char> for Terrain {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Terrain::Ash),
+            '#' => Ok(Terrain::Rock),
+            _ => Err(ParseError::new(format!(
+                "unknown terrain `{c}`, expected `.` or `#`"
+            ))),
         }
     }
+}
 
-    fn vertical_mirror_value(&self, v2: bool) -> Option<usize> {
-        let col_count = self.cols.len();
-        for col in 0..col_count - 1 {
-            let a = &self.cols[col];
-            let b = &self.cols[col + 1];
-            if a == b {
-                if self.is_valid_vertical_mirror(col, v2, false) {
-                    return Some(col + 1);
-                }
-            } else if v2 {
-                let distance = levenshtein::levenshtein(a, b);
-                if distance == 1 && self.is_valid_vertical_mirror(col, v2, true) {
-                    return Some(col + 1);
-                }
-            }
+impl std::fmt::Display for Terrain {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Terrain::Ash => write!(f, "."),
+            Terrain::Rock => write!(f, "#"),
         }
-        None
     }
+}
 
-    fn is_valid_vertical_mirror(&self, start: usize, v2: bool, was_smudged: bool) -> bool {
-        let mut was_smudged = was_smudged;
-        let mut a = start as isize - 1;
-        let mut b = start + 2;
-        while a >= 0 && b < self.cols.len() {
-            let col_a = &self.cols[a as usize];
-            let col_b = &self.cols[b];
-            if !v2 && col_a != col_b {
-                return false;
-            } else if v2 {
-                let distance = levenshtein::levenshtein(col_a, col_b);
-                match distance.cmp(&1) {
-                    std::cmp::Ordering::Less => (),
-                    std::cmp::Ordering::Equal => {
-                        // one smudge ok, two smudge get outta here
-                        // wasn't true in practice, but oh well
-                        if was_smudged {
-                            return false;
-                        }
-                        was_smudged = true;
-                    }
-                    // too many smudges
-                    std::cmp::Ordering::Greater => return false,
-                }
-            }
-            a -= 1;
-            b += 1;
-        }
+struct Map {
+    grid: Grid<Terrain>,
+}
 
-        if v2 {
-            // if it wasn't smudged, we don't want it
-            was_smudged
+impl Map {
+    /// Summarise the line of reflection that needs exactly `smudges` tiles fixed: rows above
+    /// a horizontal one count 100 each, columns left of a vertical one count 1.
+    fn find_value(&self, smudges: usize) -> usize {
+        if let Some(rows) = mirror_row(&self.grid, smudges) {
+            rows * 100
         } else {
-            true
+            mirror_row(&self.grid.transpose(), smudges).unwrap_or_default()
         }
     }
 }
 
+/// The number of rows above the first horizontal line that reflects the grid once exactly
+/// `smudges` tiles are swapped.
+fn mirror_row(grid: &Grid<Terrain>, smudges: usize) -> Option<usize> {
+    (1..grid.rows()).find(|&split| {
+        let reflected = split.min(grid.rows() - split);
+        let differences: usize = (0..reflected)
+            .map(|i| differences(grid.row(split - 1 - i), grid.row(split + i)))
+            .sum();
+        differences == smudges
+    })
+}
+
+fn differences(a: &[Terrain], b: &[Terrain]) -> usize {
+    a.iter().zip(b).filter(|(a, b)| a != b).count()
+}
+
 struct AllMaps {
     maps: Vec<Map>,
 }
 
 impl AllMaps {
-    fn parse(lines: &str) -> Self {
+    fn parse(lines: &str) -> Result<Self, ParseError> {
+        let lines: Vec<&str> = lines.lines().collect();
         let mut maps = vec![];
-        let mut rows = vec![];
-        for line in lines.lines() {
-            if line.is_empty() {
-                maps.push(Map::from_rows(rows.clone()));
-                rows = vec![];
-            } else {
-                rows.push(line.to_owned())
-            }
-        }
+        let mut first_line = 1;
         // may not have a trailing empty line
-        if !rows.is_empty() {
-            maps.push(Map::from_rows(rows.clone()));
+        for block in lines.split(|line| line.is_empty()) {
+            if !block.is_empty() {
+                let grid = Grid::parse(&block.join("\n")).map_err(|e| e.within(first_line, 1))?;
+                maps.push(Map { grid });
+            }
+            first_line += block.len() + 1;
         }
-        Self { maps }
+        Ok(Self { maps })
     }
 
-    fn sum_values(&self, v2: bool) -> usize {
-        self.maps.iter().map(|m| m.find_value(v2)).sum()
+    fn sum_values(&self, smudges: usize) -> usize {
+        self.maps.iter().map(|m| m.find_value(smudges)).sum()
     }
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day13.txt")
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::day13::{answer_part_1, answer_part_2, get_input_string};
+    use crate::aoc2023::day13::{answer_part_1, answer_part_2};
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 13, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 27202);
-        assert_eq!(answer_part_2(lines), 41566);
+        assert_eq!(answer_part_1(lines), Ok(27202));
+        assert_eq!(answer_part_2(lines), Ok(41566));
     }
 
     const SAMPLE_INPUT: &str = r"#.##..##.
@@ -217,7 +148,16 @@ mod tests {
 
     #[test]
     fn test_point_of_incidence() {
-        assert_eq!(answer_part_1(SAMPLE_INPUT), 405);
-        assert_eq!(answer_part_2(SAMPLE_INPUT), 400);
+        assert_eq!(answer_part_1(SAMPLE_INPUT), Ok(405));
+        assert_eq!(answer_part_2(SAMPLE_INPUT), Ok(400));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let second_map_typo = SAMPLE_INPUT.replacen("#....#..#", "#....x..#", 1);
+        assert_eq!(
+            answer_part_1(&second_map_typo).unwrap_err().to_string(),
+            "line 10, column 6: unknown terrain `x`, expected `.` or `#`"
+        );
     }
 }
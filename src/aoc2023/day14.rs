@@ -1,85 +1,70 @@
-use grid::{grid, Grid};
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
-}
+use crate::{answer::Answer, cycle, grid::Grid, parse::ParseError, solution::Solution};
 
-fn answer_part_1(lines: &str) -> usize {
-    let mut field = parse(lines);
+pub(crate) struct Day14;
 
-    field.shift_load_north();
-    field.print();
-    field.calculate_load()
-}
+impl Solution for Day14 {
+    fn year(&self) -> u16 {
+        2023
+    }
 
-fn answer_part_2(lines: &str) -> usize {
-    let mut field = parse(lines);
+    fn day(&self) -> usize {
+        14
+    }
 
-    let mut load_history = vec![];
-    for _i in 0..1_000 {
-        // north
-        field.shift_load_north();
-        field.grid.rotate_right();
-        // west
-        field.shift_load_north();
-        field.grid.rotate_right();
-        // south
-        field.shift_load_north();
-        field.grid.rotate_right();
-        // east
-        field.shift_load_north();
-        field.grid.rotate_right();
-        let new_load = field.calculate_load();
-        load_history.push(new_load);
+    fn title(&self) -> &'static str {
+        "Parabolic Reflector Dish"
     }
-    println!("{load_history:?}");
-    let size = find_load_history_pattern_size(&load_history);
-    println!("{size}");
-    let history_len = load_history.len();
-    let pattern = load_history[(history_len - size)..history_len].to_vec();
-
-    let location = 1_000_000_000 % size;
-    let current = (size - 1) - (history_len % size);
-    println!("{pattern:?}");
-    println!("{history_len} {location} {current}");
-    pattern[location + current]
-}
 
-fn find_load_history_pattern_size(load_history: &Vec<usize>) -> usize {
-    let history_len = load_history.len() - 1;
-    for i in 3..500 {
-        let mut comparison = load_history[(history_len - i)..history_len].to_vec();
-        comparison.append(&mut comparison.clone());
-        let comparison_size = comparison.len();
-        if comparison.iter().enumerate().any(|(j, v)| {
-            let offset = comparison_size - j;
-            load_history[history_len - offset] != *v
-        }) {
-            // we never found a mismatch, so this is the size of the pattern
-            return i;
-        }
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_1(input).map(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        answer_part_2(input).map(Into::into)
     }
-    0
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day14.txt")
+fn answer_part_1(lines: &str) -> Result<usize, ParseError> {
+    let mut field = parse(lines)?;
+
+    field.shift_load_north();
+    Ok(field.calculate_load())
+}
+
+fn answer_part_2(lines: &str) -> Result<usize, ParseError> {
+    let field = parse(lines)?;
+    let grid = cycle::state_at(field.grid, spin_cycle, 1_000_000_000);
+    Ok(FocusField { grid }.calculate_load())
 }
 
-#[derive(PartialEq, Clone, Copy)]
+/// Tilt north, west, south then east, leaving north up again.
+fn spin_cycle(grid: &Grid<Space>) -> Grid<Space> {
+    let mut field = FocusField { grid: grid.clone() };
+    for _ in 0..4 {
+        field.shift_load_north();
+        field.grid = field.grid.rotate_right();
+    }
+    field.grid
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
 enum Space {
     Empty,
     RoundRock,
     CubeRock,
 }
 
-impl From<char> for Space {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Space {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            '.' => Space::Empty,
-            'O' => Space::RoundRock,
-            '#' => Space::CubeRock,
-            _ => panic!("no such space type"),
+            '.' => Ok(Space::Empty),
+            'O' => Ok(Space::RoundRock),
+            '#' => Ok(Space::CubeRock),
+            _ => Err(ParseError::new(format!(
+                "no such space type `{c}`, expected `.`, `O` or `#`"
+            ))),
         }
     }
 }
@@ -101,16 +86,10 @@ struct FocusField {
 impl FocusField {
     fn calculate_load(&self) -> usize {
         let rows = self.grid.rows();
-        let _cols = self.grid.cols();
-        let mut total_load = 0;
-        for ((row, _column), v) in self.grid.indexed_iter() {
-            if v == &Space::RoundRock {
-                let row_value = rows - row;
-                total_load += row_value;
-            }
-        }
-
-        total_load
+        self.grid
+            .positions(|space| space == &Space::RoundRock)
+            .map(|(row, _column)| rows - row)
+            .sum()
     }
 
     fn shift_load_north(&mut self) {
@@ -146,43 +125,24 @@ impl FocusField {
             }
         }
     }
-
-    fn print(&self) {
-        let mut output = String::new();
-        let cols = self.grid.cols() - 1;
-        for ((_, col), v) in self.grid.indexed_iter() {
-            output.push_str(&v.to_string());
-            if col == cols {
-                output.push('\n');
-            }
-        }
-
-        println!("{output}");
-    }
 }
 
-fn parse(lines: &str) -> FocusField {
-    let mut grid = grid![];
-    let lines: Vec<Vec<Space>> = lines
-        .lines()
-        .map(|line| line.chars().map(|c| c.into()).collect::<Vec<Space>>())
-        .collect();
-    for line in lines {
-        grid.push_row(line);
-    }
-
-    FocusField { grid }
+fn parse(lines: &str) -> Result<FocusField, ParseError> {
+    Ok(FocusField {
+        grid: Grid::parse(lines)?,
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day14::{answer_part_1, answer_part_2, get_input_string};
+    use crate::aoc2023::day14::{answer_part_1, answer_part_2};
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 14, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 102497);
-        assert_eq!(answer_part_2(lines), 105008);
+        assert_eq!(answer_part_1(lines), Ok(102497));
+        assert_eq!(answer_part_2(lines), Ok(105008));
     }
 
     const SAMPLE_INPUT: &str = r#"O....#....
@@ -198,7 +158,20 @@ O.#..O.#.#
 
     #[test]
     fn test_reflector_dish_load() {
-        assert_eq!(answer_part_1(SAMPLE_INPUT), 136);
-        assert_eq!(answer_part_2(SAMPLE_INPUT), 64);
+        assert_eq!(answer_part_1(SAMPLE_INPUT), Ok(136));
+        assert_eq!(answer_part_2(SAMPLE_INPUT), Ok(64));
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        let truncated = &SAMPLE_INPUT[..SAMPLE_INPUT.len() - 4];
+        assert_eq!(
+            answer_part_1(truncated).unwrap_err().to_string(),
+            "line 10, column 7: expected a row of 10 tiles, found 6"
+        );
+        assert_eq!(
+            answer_part_1("O..\n.x.").unwrap_err().to_string(),
+            "line 2, column 2: no such space type `x`, expected `.`, `O` or `#`"
+        );
     }
 }
@@ -1,6 +1,27 @@
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+use crate::{answer::Answer, parse::ParseError, solution::Solution};
+
+pub(crate) struct Day15;
+
+impl Solution for Day15 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        15
+    }
+
+    fn title(&self) -> &'static str {
+        "Lens Library"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_1(input).into())
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_2(input).into())
+    }
 }
 
 fn answer_part_1(lines: &str) -> usize {
@@ -17,7 +38,6 @@ fn answer_part_2(lines: &str) -> usize {
     let mut storage = Storage {
         boxes: std::collections::HashMap::new(),
     };
-    println!("{ops:?}");
     for op in ops {
         storage.run_operation(&op);
     }
@@ -33,10 +53,6 @@ fn answer_part_2(lines: &str) -> usize {
     sum
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day15.txt")
-}
-
 struct Storage {
     boxes: std::collections::HashMap<usize, Vec<LabelOperation>>,
 }
@@ -106,10 +122,11 @@ fn hash_algorithm(input: &str) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use crate::day15::{answer_part_1, answer_part_2, get_input_string, hash_algorithm};
+    use crate::aoc2023::day15::{answer_part_1, answer_part_2, hash_algorithm};
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 15, &InputSource::default()).unwrap();
 
         assert_eq!(answer_part_1(lines), 517965);
         assert_eq!(answer_part_2(lines), 267372);
@@ -1,32 +1,51 @@
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
-}
+use crate::{answer::Answer, parse::ParseError, solution::Solution};
+
+pub(crate) struct Day16;
+
+impl Solution for Day16 {
+    fn year(&self) -> u16 {
+        2023
+    }
+
+    fn day(&self) -> usize {
+        16
+    }
 
-fn answer_part_1(lines: &str) -> usize {
-    lines.len()
+    fn title(&self) -> &'static str {
+        "The Floor Will Be Lava"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_1(input))
+    }
+
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(answer_part_2(input))
+    }
 }
 
-fn answer_part_2(lines: &str) -> usize {
-    lines.len()
+fn answer_part_1(_lines: &str) -> Answer {
+    Answer::Unsolved
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day16.txt")
+fn answer_part_2(_lines: &str) -> Answer {
+    Answer::Unsolved
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day16::{answer_part_1, answer_part_2, get_input_string};
+    use crate::answer::Answer;
+    use crate::aoc2023::day16::{answer_part_1, answer_part_2};
+    use crate::input::{self, InputSource};
     #[test]
     fn test_all_lines() {
-        let lines = get_input_string();
+        let lines = &input::load(2023, 16, &InputSource::default()).unwrap();
 
-        assert_eq!(answer_part_1(lines), 0);
-        assert_eq!(answer_part_2(lines), 0);
+        assert_eq!(answer_part_1(lines), Answer::Unsolved);
+        assert_eq!(answer_part_2(lines), Answer::Unsolved);
     }
 
-    const SAMPLE_INPUT: &'static str = r".|...\....
+    const SAMPLE_INPUT: &str = r".|...\....
 |.-.\.....
 .....|-...
 ........|.
@@ -38,7 +57,8 @@ mod tests {
 ..//.|....";
 
     #[test]
+    #[ignore = "day 16 is not solved yet"]
     fn test_sample_input() {
-        assert_eq!(answer_part_1(SAMPLE_INPUT), 46);
+        assert_eq!(answer_part_1(SAMPLE_INPUT), Answer::Integer(46));
     }
 }
@@ -14,3 +14,24 @@ mod day13;
 mod day14;
 mod day15;
 mod day16;
+
+use crate::solution::Registry;
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.register(day01::Day01);
+    registry.register(day02::Day02);
+    registry.register(day03::Day03);
+    registry.register(day04::Day04);
+    registry.register(day05::Day05);
+    registry.register(day06::Day06);
+    registry.register(day07::Day07);
+    registry.register(day08::Day08);
+    registry.register(day09::Day09);
+    registry.register(day10::Day10);
+    registry.register(day11::Day11);
+    registry.register(day12::Day12);
+    registry.register(day13::Day13);
+    registry.register(day14::Day14);
+    registry.register(day15::Day15);
+    registry.register(day16::Day16);
+}
@@ -0,0 +1,181 @@
+use crate::runner::format_duration;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, time::Duration};
+
+/// Summary statistics over repeated runs of one part, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Stats {
+    pub(crate) runs: usize,
+    pub(crate) mean: f64,
+    pub(crate) median: f64,
+    pub(crate) stddev: f64,
+    pub(crate) min: f64,
+    pub(crate) max: f64,
+}
+
+impl Stats {
+    pub(crate) fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "need at least one sample");
+        let mut nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        nanos.sort_by(|a, b| a.total_cmp(b));
+
+        let runs = nanos.len();
+        let mean = nanos.iter().sum::<f64>() / runs as f64;
+        let median = if runs.is_multiple_of(2) {
+            (nanos[runs / 2 - 1] + nanos[runs / 2]) / 2.0
+        } else {
+            nanos[runs / 2]
+        };
+        let variance = nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / runs as f64;
+
+        Self {
+            runs,
+            mean,
+            median,
+            stddev: variance.sqrt(),
+            min: nanos[0],
+            max: nanos[runs - 1],
+        }
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let fmt = |nanos: f64| format_duration(Duration::from_nanos(nanos as u64));
+        write!(
+            f,
+            "mean {} ± {}, median {}, min {}, max {} ({} runs)",
+            fmt(self.mean),
+            fmt(self.stddev),
+            fmt(self.median),
+            fmt(self.min),
+            fmt(self.max),
+            self.runs
+        )
+    }
+}
+
+/// Run `f` `warmup` times untimed, then `runs` times timed.
+pub(crate) fn measure<T>(warmup: usize, runs: usize, f: impl Fn() -> T) -> Stats {
+    for _ in 0..warmup {
+        std::hint::black_box(f());
+    }
+    let samples: Vec<Duration> = (0..runs.max(1))
+        .map(|_| crate::runner::time(|| std::hint::black_box(f())).1)
+        .collect();
+    Stats::from_samples(&samples)
+}
+
+/// Benchmark results for one day, stored as JSON so later runs can be compared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Baseline {
+    pub(crate) year: u16,
+    pub(crate) day: usize,
+    pub(crate) part_one: Stats,
+    pub(crate) part_two: Stats,
+}
+
+impl Baseline {
+    pub(crate) fn load(path: &Path) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Verdict {
+    Regression,
+    Improvement,
+    Unchanged,
+}
+
+/// Compare mean times, treating changes within `threshold_percent` as noise.
+pub(crate) fn compare(baseline: &Stats, current: &Stats, threshold_percent: f64) -> (f64, Verdict) {
+    let change = (current.mean - baseline.mean) / baseline.mean * 100.0;
+    let verdict = if change > threshold_percent {
+        Verdict::Regression
+    } else if change < -threshold_percent {
+        Verdict::Improvement
+    } else {
+        Verdict::Unchanged
+    };
+    (change, verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::{compare, measure, Baseline, Stats, Verdict};
+    use std::time::Duration;
+
+    #[test]
+    fn test_stats_from_samples() {
+        let samples: Vec<Duration> = [4, 2, 8, 6]
+            .iter()
+            .map(|n| Duration::from_nanos(*n))
+            .collect();
+        let stats = Stats::from_samples(&samples);
+
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.median, 5.0);
+        assert_eq!(stats.min, 2.0);
+        assert_eq!(stats.max, 8.0);
+        assert!((stats.stddev - 5.0f64.sqrt()).abs() < 1e-9);
+
+        let odd = Stats::from_samples(&samples[0..3]);
+        assert_eq!(odd.median, 4.0);
+    }
+
+    #[test]
+    fn test_measure_counts_runs() {
+        let calls = std::cell::Cell::new(0);
+        let stats = measure(2, 5, || calls.set(calls.get() + 1));
+        assert_eq!(calls.get(), 7);
+        assert_eq!(stats.runs, 5);
+    }
+
+    #[test]
+    fn test_compare_against_baseline() {
+        let stats = |mean| Stats {
+            runs: 1,
+            mean,
+            median: mean,
+            stddev: 0.0,
+            min: mean,
+            max: mean,
+        };
+        assert_eq!(
+            compare(&stats(100.0), &stats(150.0), 10.0),
+            (50.0, Verdict::Regression)
+        );
+        assert_eq!(
+            compare(&stats(100.0), &stats(50.0), 10.0),
+            (-50.0, Verdict::Improvement)
+        );
+        assert_eq!(
+            compare(&stats(100.0), &stats(105.0), 10.0),
+            (5.0, Verdict::Unchanged)
+        );
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let stats = Stats::from_samples(&[Duration::from_micros(3)]);
+        let baseline = Baseline {
+            year: 2023,
+            day: 9,
+            part_one: stats.clone(),
+            part_two: stats,
+        };
+        let path = std::env::temp_dir().join("aoc-bench-test/baseline.json");
+        baseline.save(&path).unwrap();
+        assert_eq!(Baseline::load(&path).unwrap(), baseline);
+    }
+}
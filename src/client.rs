@@ -0,0 +1,366 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub(crate) const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "github.com/iterion/advent-of-code";
+
+lazy_static! {
+    static ref MAIN_RE: Regex = Regex::new(r"(?s)<main>(.*)</main>").unwrap();
+    static ref ARTICLE_RE: Regex = Regex::new(r"(?s)<article>(.*?)</article>").unwrap();
+    static ref TAG_RE: Regex = Regex::new(r"<[^>]*>").unwrap();
+    static ref LEFT_TO_WAIT_RE: Regex = Regex::new(r"(?:(\d+)m )?(\d+)s left to wait").unwrap();
+    static ref WAIT_MINUTES_RE: Regex =
+        Regex::new(r"wait (one|\d+) minutes? before trying again").unwrap();
+}
+
+#[derive(Debug)]
+pub(crate) enum ClientError {
+    MissingSession,
+    Status(u16, String),
+    Transport(String),
+    Io(std::io::Error),
+    /// The response from a URL was cut off or couldn't be decoded.
+    Body(String, std::io::Error),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ClientError::MissingSession => write!(
+                f,
+                "no session cookie found, set AOC_SESSION, write it to ~/.adventofcode.session or set session_file in aoc.toml"
+            ),
+            ClientError::Status(code, url) => write!(f, "{url} returned HTTP {code}"),
+            ClientError::Transport(message) => write!(f, "request failed: {message}"),
+            ClientError::Io(err) => write!(f, "couldn't read session file: {err}"),
+            ClientError::Body(url, err) => write!(f, "couldn't read the response from {url}: {err}"),
+        }
+    }
+}
+
+impl From<ureq::Error> for ClientError {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::Status(code, response) => {
+                ClientError::Status(code, response.get_url().to_owned())
+            }
+            ureq::Error::Transport(transport) => ClientError::Transport(transport.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(err: std::io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    RateLimited,
+    AlreadyCompleted,
+    Unknown,
+}
+
+/// The site's response to a submitted answer.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SubmissionResult {
+    pub(crate) verdict: Verdict,
+    /// How long the site asks us to wait before submitting again.
+    pub(crate) wait: Option<Duration>,
+    /// The response text with markup removed.
+    pub(crate) message: String,
+}
+
+impl SubmissionResult {
+    pub(crate) fn parse(page: &str) -> Self {
+        let article = ARTICLE_RE
+            .captures(page)
+            .map_or(page, |c| c.get(1).unwrap().as_str());
+        let message = html_escape::decode_html_entities(&TAG_RE.replace_all(article, ""))
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let verdict = if message.contains("That's the right answer") {
+            Verdict::Correct
+        } else if message.contains("You gave an answer too recently") {
+            Verdict::RateLimited
+        } else if message.contains("Did you already complete it") {
+            Verdict::AlreadyCompleted
+        } else if message.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if message.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if message.contains("That's not the right answer") {
+            Verdict::Wrong
+        } else {
+            Verdict::Unknown
+        };
+
+        let wait = if let Some(c) = LEFT_TO_WAIT_RE.captures(&message) {
+            let minutes: u64 = c.get(1).map_or(0, |m| m.as_str().parse().unwrap());
+            let seconds: u64 = c[2].parse().unwrap();
+            Some(Duration::from_secs(minutes * 60 + seconds))
+        } else {
+            WAIT_MINUTES_RE.captures(&message).map(|c| {
+                let minutes = if &c[1] == "one" {
+                    1
+                } else {
+                    c[1].parse().unwrap()
+                };
+                Duration::from_secs(minutes * 60)
+            })
+        };
+
+        Self {
+            verdict,
+            wait,
+            message,
+        }
+    }
+}
+
+/// Where the session cookie is looked up when `AOC_SESSION` isn't set and no session file is
+/// configured, matching aoc-cli.
+fn default_session_file() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".adventofcode.session"))
+}
+
+/// The session cookie from `AOC_SESSION`, or else read from `session_file`, falling back to
+/// `~/.adventofcode.session`.
+pub(crate) fn find_session(session_file: Option<&Path>) -> Result<String, ClientError> {
+    if let Ok(session) = std::env::var("AOC_SESSION") {
+        return Ok(session.trim().to_owned());
+    }
+    if let Some(path) = session_file {
+        return Ok(std::fs::read_to_string(path)?.trim().to_owned());
+    }
+    match default_session_file() {
+        Some(path) if path.exists() => Ok(std::fs::read_to_string(path)?.trim().to_owned()),
+        _ => Err(ClientError::MissingSession),
+    }
+}
+
+/// Blocking client for the Advent of Code site.
+pub(crate) struct AocClient {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl AocClient {
+    pub(crate) fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    fn day_url(&self, year: u16, day: usize) -> String {
+        format!("{}/{year}/day/{day}", self.base_url)
+    }
+
+    fn get(&self, url: &str) -> Result<String, ClientError> {
+        let body = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()?
+            .into_string()
+            .map_err(|err| ClientError::Body(url.to_owned(), err))?;
+        Ok(body)
+    }
+
+    pub(crate) fn download_input(&self, year: u16, day: usize) -> Result<String, ClientError> {
+        self.get(&format!("{}/input", self.day_url(year, day)))
+    }
+
+    /// The puzzle description converted to markdown, in the same format aoc-cli writes.
+    pub(crate) fn download_puzzle(&self, year: u16, day: usize) -> Result<String, ClientError> {
+        let page = self.get(&self.day_url(year, day))?;
+        let main = MAIN_RE
+            .captures(&page)
+            .map_or(page.as_str(), |c| c.get(1).unwrap().as_str());
+        Ok(html2md::parse_html(main))
+    }
+
+    pub(crate) fn submit(
+        &self,
+        year: u16,
+        day: usize,
+        part: usize,
+        answer: &str,
+    ) -> Result<SubmissionResult, ClientError> {
+        let url = format!("{}/answer", self.day_url(year, day));
+        let page = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?
+            .into_string()
+            .map_err(|err| ClientError::Body(url, err))?;
+        Ok(SubmissionResult::parse(&page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{AocClient, ClientError, SubmissionResult, Verdict};
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        time::Duration,
+    };
+
+    /// Serve one canned response per entry in `responses` and send back each raw request.
+    fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).unwrap();
+                request.push_str(&String::from_utf8(request_body).unwrap());
+                sender.send(request).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (base_url, receiver)
+    }
+
+    #[test]
+    fn test_download_input_and_puzzle() {
+        let (base_url, requests) = stub_server(vec![
+            (200, "0 3 6 9 12 15\n"),
+            (
+                200,
+                "<html><body><main><article><h2>--- Day 9: Mirage Maintenance ---</h2><p>Some <em>text</em>.</p></article></main></body></html>",
+            ),
+        ]);
+        let client = AocClient::new(&base_url, "abc123");
+
+        assert_eq!(client.download_input(2023, 9).unwrap(), "0 3 6 9 12 15\n");
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET /2023/day/9/input "));
+        assert!(request.contains("session=abc123"));
+
+        let puzzle = client.download_puzzle(2023, 9).unwrap();
+        assert!(puzzle.contains("Day 9: Mirage Maintenance"));
+        assert!(puzzle.contains("Some *text*."));
+        assert!(requests.recv().unwrap().starts_with("GET /2023/day/9 "));
+    }
+
+    #[test]
+    fn test_submit_answer() {
+        let (base_url, requests) = stub_server(vec![(
+            200,
+            "<main><article><p>That's the right answer! You are <em>one gold star</em> closer.</p></article></main>",
+        )]);
+        let client = AocClient::new(&base_url, "abc123");
+
+        let result = client.submit(2023, 9, 2, "923").unwrap();
+        assert_eq!(result.verdict, Verdict::Correct);
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /2023/day/9/answer "));
+        assert!(request.ends_with("level=2&answer=923"));
+    }
+
+    #[test]
+    fn test_http_error_status() {
+        let (base_url, _requests) = stub_server(vec![(404, "not found")]);
+        let client = AocClient::new(&base_url, "abc123");
+
+        assert!(matches!(
+            client.download_input(2023, 30),
+            Err(ClientError::Status(404, _))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while line != "\r\n" {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+            }
+            write!(
+                reader.into_inner(),
+                "HTTP/1.1 200 OK\r\nContent-Length: 100\r\nConnection: close\r\n\r\n0 3 6"
+            )
+            .unwrap();
+        });
+        let client = AocClient::new(&base_url, "abc123");
+
+        let err = client.download_input(2023, 9).unwrap_err();
+        assert!(matches!(err, ClientError::Body(..)));
+        assert!(err.to_string().starts_with(&format!(
+            "couldn't read the response from {base_url}/2023/day/9/input: "
+        )));
+    }
+
+    #[test]
+    fn test_parse_submission_results() {
+        let wrong = SubmissionResult::parse("<article><p>That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2023/about\">about page</a>, or you can ask for hints on the <a href=\"https://www.reddit.com/r/adventofcode/\" target=\"_blank\">subreddit</a>.  Please wait one minute before trying again. <a href=\"/2023/day/12\">[Return to Day 12]</a></p></article>");
+        assert_eq!(wrong.verdict, Verdict::TooHigh);
+        assert_eq!(wrong.wait, Some(Duration::from_secs(60)));
+
+        let low = SubmissionResult::parse("<article><p>That's not the right answer; your answer is too low. Please wait 5 minutes before trying again.</p></article>");
+        assert_eq!(low.verdict, Verdict::TooLow);
+        assert_eq!(low.wait, Some(Duration::from_secs(300)));
+
+        let plain = SubmissionResult::parse("<article><p>That's not the right answer. If you're stuck, make sure you're using the full input data.</p></article>");
+        assert_eq!(plain.verdict, Verdict::Wrong);
+        assert_eq!(plain.wait, None);
+
+        let limited = SubmissionResult::parse("<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 23s left to wait. <a href=\"/2023/day/12\">[Return to Day 12]</a></p></article>");
+        assert_eq!(limited.verdict, Verdict::RateLimited);
+        assert_eq!(limited.wait, Some(Duration::from_secs(83)));
+
+        let done = SubmissionResult::parse("<article><p>You don't seem to be solving the right level.  Did you already complete it? <a href=\"/2023/day/12\">[Return to Day 12]</a></p></article>");
+        assert_eq!(done.verdict, Verdict::AlreadyCompleted);
+        assert!(done.message.starts_with("You don't seem"));
+    }
+}
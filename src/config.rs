@@ -0,0 +1,233 @@
+use chrono::Datelike;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub(crate) const DEFAULT_CONFIG_FILE: &str = "aoc.toml";
+
+/// Settings shared by every command, read once from `aoc.toml` in the working directory.
+/// Everything is optional:
+///
+/// ```toml
+/// year = 2023
+/// base_url = "https://adventofcode.com"
+/// session_file = "/home/me/.config/aoc/session"
+/// input_dir = "inputs"
+/// puzzle_dir = "puzzles"
+/// answer_dir = "answers"
+/// template_dir = "templates"
+///
+/// [llm]
+/// base_url = "http://localhost:11434/v1"
+/// model = "llama3"
+/// ```
+///
+/// Environment variables override the file (see [`Config::apply_env`]) and command line
+/// flags override both.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    /// Puzzle year when `--year` isn't given; see [`Config::year`].
+    pub(crate) year: Option<u16>,
+    /// The Advent of Code server, replaceable for testing against a local copy.
+    pub(crate) base_url: String,
+    /// Where to read the session cookie when `AOC_SESSION` isn't set.
+    pub(crate) session_file: Option<PathBuf>,
+    pub(crate) input_dir: PathBuf,
+    pub(crate) puzzle_dir: PathBuf,
+    pub(crate) answer_dir: PathBuf,
+    pub(crate) template_dir: PathBuf,
+    pub(crate) llm: LlmSettings,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct LlmSettings {
+    pub(crate) base_url: String,
+    pub(crate) model: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: None,
+            base_url: crate::client::DEFAULT_BASE_URL.to_owned(),
+            session_file: None,
+            input_dir: PathBuf::from(crate::input::DEFAULT_INPUT_DIR),
+            puzzle_dir: PathBuf::from(crate::puzzle::DEFAULT_PUZZLE_DIR),
+            answer_dir: PathBuf::from(crate::answer_store::DEFAULT_ANSWER_DIR),
+            template_dir: PathBuf::from(crate::scaffold::DEFAULT_TEMPLATE_DIR),
+            llm: LlmSettings::default(),
+        }
+    }
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            base_url: crate::llm::DEFAULT_BASE_URL.to_owned(),
+            model: crate::llm::DEFAULT_MODEL.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ConfigError {
+    Read(PathBuf, std::io::Error),
+    Invalid(PathBuf, String),
+    Env(&'static str, String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ConfigError::Read(path, err) => write!(f, "couldn't read {}: {err}", path.display()),
+            ConfigError::Invalid(path, message) => {
+                write!(f, "{} is invalid: {message}", path.display())
+            }
+            ConfigError::Env(name, value) => write!(f, "{name} has an invalid value `{value}`"),
+        }
+    }
+}
+
+impl Config {
+    /// Read the config file, then apply overrides from `env`. Without an explicit `path` a
+    /// missing `aoc.toml` just means using the defaults.
+    pub(crate) fn load(
+        path: Option<&Path>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let file = path.unwrap_or(Path::new(DEFAULT_CONFIG_FILE));
+        let mut config = match std::fs::read_to_string(file) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| ConfigError::Invalid(file.to_owned(), e.message().to_owned()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && path.is_none() => Self::default(),
+            Err(e) => return Err(ConfigError::Read(file.to_owned(), e)),
+        };
+        config.apply_env(env)?;
+        Ok(config)
+    }
+
+    /// Override settings from `AOC_YEAR`, `AOC_BASE_URL`, `AOC_SESSION_FILE`,
+    /// `AOC_INPUT_DIR`, `AOC_PUZZLE_DIR`, `AOC_ANSWER_DIR`, `AOC_TEMPLATE_DIR`,
+    /// `AOC_LLM_BASE_URL` and `AOC_LLM_MODEL`.
+    pub(crate) fn apply_env(
+        &mut self,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        if let Some(year) = env("AOC_YEAR") {
+            self.year = Some(
+                year.parse()
+                    .map_err(|_| ConfigError::Env("AOC_YEAR", year))?,
+            );
+        }
+        if let Some(base_url) = env("AOC_BASE_URL") {
+            self.base_url = base_url;
+        }
+        if let Some(path) = env("AOC_SESSION_FILE") {
+            self.session_file = Some(PathBuf::from(path));
+        }
+        for (name, dir) in [
+            ("AOC_INPUT_DIR", &mut self.input_dir),
+            ("AOC_PUZZLE_DIR", &mut self.puzzle_dir),
+            ("AOC_ANSWER_DIR", &mut self.answer_dir),
+            ("AOC_TEMPLATE_DIR", &mut self.template_dir),
+        ] {
+            if let Some(path) = env(name) {
+                *dir = PathBuf::from(path);
+            }
+        }
+        if let Some(base_url) = env("AOC_LLM_BASE_URL") {
+            self.llm.base_url = base_url;
+        }
+        if let Some(model) = env("AOC_LLM_MODEL") {
+            self.llm.model = model;
+        }
+        Ok(())
+    }
+
+    /// The configured year, or the most recent event: the current year once December
+    /// starts, otherwise last year.
+    pub(crate) fn year(&self) -> u16 {
+        self.year.unwrap_or_else(|| {
+            let today = chrono::Local::now().date_naive();
+            let year = today.year() as u16;
+            if today.month() == 12 {
+                year
+            } else {
+                year - 1
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Config, ConfigError};
+    use std::path::{Path, PathBuf};
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_missing_default_file() {
+        let dir = std::env::temp_dir().join("aoc-config-missing-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = Config::load(None, no_env).unwrap();
+        // the repo doesn't ship an aoc.toml, so these are the defaults
+        assert_eq!(config, Config::default());
+        assert_eq!(config.input_dir, PathBuf::from("inputs"));
+
+        assert!(matches!(
+            Config::load(Some(&dir.join("aoc.toml")), no_env),
+            Err(ConfigError::Read(..))
+        ));
+    }
+
+    #[test]
+    fn test_file_and_env_overrides() {
+        let path = std::env::temp_dir().join("aoc-config-test.toml");
+        std::fs::write(
+            &path,
+            "year = 2022\ninput_dir = \"data/inputs\"\n\n[llm]\nmodel = \"llama3\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load(Some(&path), no_env).unwrap();
+        assert_eq!(config.year(), 2022);
+        assert_eq!(config.input_dir, Path::new("data/inputs"));
+        assert_eq!(config.puzzle_dir, Path::new("puzzles"));
+        assert_eq!(config.llm.model, "llama3");
+        assert_eq!(config.llm.base_url, crate::llm::DEFAULT_BASE_URL);
+        assert_eq!(config.base_url, crate::client::DEFAULT_BASE_URL);
+
+        let env = |name: &str| match name {
+            "AOC_YEAR" => Some("2021".to_owned()),
+            "AOC_INPUT_DIR" => Some("elsewhere".to_owned()),
+            "AOC_SESSION_FILE" => Some("session.txt".to_owned()),
+            "AOC_BASE_URL" => Some("http://localhost:8080".to_owned()),
+            _ => None,
+        };
+        let config = Config::load(Some(&path), env).unwrap();
+        assert_eq!(config.year, Some(2021));
+        assert_eq!(config.input_dir, Path::new("elsewhere"));
+        assert_eq!(config.session_file, Some(PathBuf::from("session.txt")));
+        assert_eq!(config.base_url, "http://localhost:8080");
+
+        let bad_year = |name: &str| (name == "AOC_YEAR").then(|| "soon".to_owned());
+        assert_eq!(
+            Config::load(Some(&path), bad_year).unwrap_err().to_string(),
+            "AOC_YEAR has an invalid value `soon`"
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let path = std::env::temp_dir().join("aoc-config-typo-test.toml");
+        std::fs::write(&path, "inputs_dir = \"data\"\n").unwrap();
+        assert!(matches!(
+            Config::load(Some(&path), no_env),
+            Err(ConfigError::Invalid(..))
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Where a sequence of states `start, step(start), step(step(start)), ...` starts repeating:
+/// the states from `prefix` onwards repeat every `period` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cycle {
+    pub(crate) prefix: usize,
+    pub(crate) period: usize,
+}
+
+impl Cycle {
+    /// The earliest step that reaches the same state as step `n`, always below
+    /// `prefix + period`.
+    pub(crate) fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+}
+
+/// Step from `start` until a state comes round again, remembering each one. Returns the
+/// cycle along with every state before the repeat, so `states[cycle.equivalent_step(n)]` is
+/// the state after `n` steps. Loops forever if the states never repeat.
+pub(crate) fn find<T: Hash + Eq + Clone>(start: T, step: impl Fn(&T) -> T) -> (Cycle, Vec<T>) {
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = start;
+    loop {
+        if let Some(&prefix) = seen.get(&state) {
+            let period = states.len() - prefix;
+            return (Cycle { prefix, period }, states);
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// The state after `n` steps from `start`, however large `n` is, as long as the states
+/// eventually repeat.
+pub(crate) fn state_at<T: Hash + Eq + Clone>(start: T, step: impl Fn(&T) -> T, n: usize) -> T {
+    let (cycle, mut states) = find(start, step);
+    states.swap_remove(cycle.equivalent_step(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cycle::{find, state_at, Cycle};
+
+    // 0, 1, 2, 3, 4, 5, 2, 3, 4, 5, ...
+    fn step(n: &u32) -> u32 {
+        if *n == 5 {
+            2
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let (cycle, states) = find(0, step);
+        assert_eq!(
+            cycle,
+            Cycle {
+                prefix: 2,
+                period: 4
+            }
+        );
+        assert_eq!(states, [0, 1, 2, 3, 4, 5]);
+
+        // a state that maps to itself
+        assert_eq!(
+            find(7, |n| *n).0,
+            Cycle {
+                prefix: 0,
+                period: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_state_at() {
+        let (cycle, _) = find(0, step);
+        assert_eq!(cycle.equivalent_step(1), 1);
+        assert_eq!(cycle.equivalent_step(6), 2);
+        assert_eq!(cycle.equivalent_step(1_000_000_000), 4);
+        for n in [0, 1, 5, 6, 13, 1_000_000_000] {
+            let expected = if n < 6 {
+                n as u32
+            } else {
+                2 + (n as u32 - 2) % 4
+            };
+            assert_eq!(state_at(0, step, n), expected);
+        }
+    }
+}
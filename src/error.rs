@@ -0,0 +1,335 @@
+use crate::{
+    client::ClientError,
+    config::ConfigError,
+    input::{self, InputSource},
+    llm::LlmError,
+    parse::ParseError,
+    sample_tests::GenerationError,
+};
+use std::path::{Path, PathBuf};
+
+/// Everything a command can fail with. Each kind of failure exits with its own code so
+/// scripts can tell them apart; 2 is left to clap for usage errors. A few variants are the
+/// same kind on purpose: `Mismatch` and `Regression` are checks that ran but didn't pass,
+/// and `NotImplemented` and `Unsolved` both mean there's no solution to run.
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// A file the tool reads or writes, other than puzzle input.
+    Io {
+        path: PathBuf,
+        err: std::io::Error,
+    },
+    MissingInput {
+        year: u16,
+        day: usize,
+        source: InputSource,
+        err: std::io::Error,
+    },
+    /// A solution rejected its puzzle input.
+    Parse {
+        year: u16,
+        day: usize,
+        source: InputSource,
+        err: ParseError,
+    },
+    Network(ClientError),
+    Config(ConfigError),
+    Llm(GenerationError),
+    Template(String),
+    NotImplemented {
+        year: u16,
+        day: usize,
+        implemented: Vec<usize>,
+    },
+    Unsolved {
+        year: u16,
+        day: usize,
+        part: usize,
+    },
+    /// The submission log or cooldown stopped an answer from being sent.
+    Refused(String),
+    /// Answers that differ from the recorded ones, and days that couldn't be run at all,
+    /// found by `verify`.
+    Mismatch {
+        count: usize,
+        failed: usize,
+        store: PathBuf,
+    },
+    Regression {
+        threshold: f64,
+    },
+}
+
+impl Error {
+    /// A closure for `map_err` that attaches `path` to an IO error.
+    pub(crate) fn io(path: impl AsRef<Path>) -> impl FnOnce(std::io::Error) -> Error {
+        let path = path.as_ref().to_owned();
+        move |err| Error::Io { path, err }
+    }
+
+    pub(crate) fn missing_input(
+        year: u16,
+        day: usize,
+        source: &InputSource,
+    ) -> impl FnOnce(std::io::Error) -> Error {
+        let source = source.clone();
+        move |err| Error::MissingInput {
+            year,
+            day,
+            source,
+            err,
+        }
+    }
+
+    pub(crate) fn parse(
+        year: u16,
+        day: usize,
+        source: &InputSource,
+    ) -> impl FnOnce(ParseError) -> Error {
+        let source = source.clone();
+        move |err| Error::Parse {
+            year,
+            day,
+            source,
+            err,
+        }
+    }
+
+    pub(crate) fn exit_code(&self) -> i32 {
+        match self {
+            // checks that ran fine but didn't pass, like a failing test suite
+            Error::Mismatch { .. } | Error::Regression { .. } => 1,
+            Error::Io { .. } => 3,
+            Error::MissingInput { .. } => 4,
+            Error::Network(_) => 5,
+            Error::Llm(_) => 6,
+            Error::Template(_) => 7,
+            Error::NotImplemented { .. } | Error::Unsolved { .. } => 8,
+            Error::Refused(_) => 9,
+            Error::Parse { .. } => 10,
+            Error::Config(_) => 11,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Io { path, err } => write!(f, "couldn't access {}: {err}", path.display()),
+            Error::MissingInput {
+                year,
+                day,
+                source,
+                err,
+            } => {
+                write!(f, "couldn't read {}: {err}", input_name(*year, *day, source))?;
+                if let InputSource::Dir(_) = source {
+                    write!(f, "\nrun `bootstrap {day}` to download it, or pass --input")?;
+                }
+                Ok(())
+            }
+            Error::Parse {
+                year,
+                day,
+                source,
+                err,
+            } => write!(
+                f,
+                "couldn't parse {} at {err}\ncheck that it's the whole input for {year} day {day}",
+                input_name(*year, *day, source)
+            ),
+            Error::Network(ClientError::Status(400, url)) => write!(
+                f,
+                "{url} returned HTTP 400; the session cookie has probably expired, update AOC_SESSION"
+            ),
+            Error::Network(ClientError::Status(404, url)) => write!(
+                f,
+                "{url} returned HTTP 404; that puzzle isn't unlocked yet"
+            ),
+            Error::Network(err) => write!(f, "{err}"),
+            Error::Config(err) => write!(f, "{err}"),
+            Error::Llm(err) => write!(f, "couldn't generate sample tests: {err}"),
+            Error::Template(message) => write!(f, "{message}"),
+            Error::NotImplemented {
+                year,
+                day,
+                implemented,
+            } => {
+                writeln!(f, "day {day} of {year} is not implemented yet")?;
+                if implemented.is_empty() {
+                    write!(f, "no days are implemented for {year}")
+                } else {
+                    let days: Vec<String> = implemented.iter().map(|d| d.to_string()).collect();
+                    write!(f, "implemented days for {year}: {}", days.join(", "))
+                }
+            }
+            Error::Unsolved { year, day, part } => {
+                write!(f, "part {part} of {year} day {day} is not solved yet")
+            }
+            Error::Refused(reason) => write!(f, "{reason}"),
+            Error::Mismatch {
+                count,
+                failed,
+                store,
+            } => {
+                write!(f, "{count} answer(s) differ from {}", store.display())?;
+                if *failed > 0 {
+                    write!(f, " and {failed} day(s) failed to run")?;
+                }
+                Ok(())
+            }
+            Error::Regression { threshold } => {
+                write!(f, "regression beyond {threshold}% detected")
+            }
+        }
+    }
+}
+
+fn input_name(year: u16, day: usize, source: &InputSource) -> String {
+    match source {
+        InputSource::Dir(dir) => input::path(dir, year, day).display().to_string(),
+        InputSource::File(path) => path.display().to_string(),
+        InputSource::Stdin => "input from stdin".to_owned(),
+    }
+}
+
+impl From<ClientError> for Error {
+    fn from(err: ClientError) -> Self {
+        Error::Network(err)
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Error::Config(err)
+    }
+}
+
+impl From<GenerationError> for Error {
+    fn from(err: GenerationError) -> Self {
+        Error::Llm(err)
+    }
+}
+
+impl From<LlmError> for Error {
+    fn from(err: LlmError) -> Self {
+        Error::Llm(GenerationError::Llm(err))
+    }
+}
+
+impl From<handlebars::RenderError> for Error {
+    fn from(err: handlebars::RenderError) -> Self {
+        Error::Template(format!("couldn't render template: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Error, input::InputSource};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_exit_codes_by_kind() {
+        let io = || std::io::Error::from(std::io::ErrorKind::NotFound);
+        // variants of the same kind of failure, which share an exit code
+        let kinds = [
+            vec![
+                Error::Mismatch {
+                    count: 1,
+                    failed: 0,
+                    store: PathBuf::new(),
+                },
+                Error::Regression { threshold: 10.0 },
+            ],
+            vec![Error::io("answers/2023.toml")(io())],
+            vec![Error::MissingInput {
+                year: 2023,
+                day: 5,
+                source: InputSource::default(),
+                err: io(),
+            }],
+            vec![Error::Network(crate::client::ClientError::MissingSession)],
+            vec![Error::Llm(crate::sample_tests::GenerationError::Rejected(
+                vec![],
+            ))],
+            vec![Error::Template(String::new())],
+            vec![
+                Error::NotImplemented {
+                    year: 2023,
+                    day: 25,
+                    implemented: vec![],
+                },
+                Error::Unsolved {
+                    year: 2023,
+                    day: 5,
+                    part: 2,
+                },
+            ],
+            vec![Error::Refused(String::new())],
+            vec![Error::Parse {
+                year: 2023,
+                day: 5,
+                source: InputSource::Stdin,
+                err: crate::parse::ParseError::new(""),
+            }],
+            vec![Error::Config(crate::config::ConfigError::Env(
+                "AOC_YEAR",
+                String::new(),
+            ))],
+        ];
+        let mut codes = vec![];
+        for kind in &kinds {
+            let code = kind[0].exit_code();
+            assert!(kind.iter().all(|err| err.exit_code() == code), "{kind:?}");
+            codes.push(code);
+        }
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), kinds.len());
+        assert!(!codes.contains(&0) && !codes.contains(&2));
+    }
+
+    #[test]
+    fn test_missing_input_suggests_bootstrap() {
+        let err = Error::MissingInput {
+            year: 2023,
+            day: 5,
+            source: InputSource::default(),
+            err: std::io::Error::from(std::io::ErrorKind::NotFound),
+        };
+        let message = err.to_string();
+        assert!(message.starts_with("couldn't read inputs/2023/day05.txt: "));
+        assert!(message.ends_with("run `bootstrap 5` to download it, or pass --input"));
+    }
+
+    #[test]
+    fn test_parse_error_names_the_input() {
+        let err = Error::parse(2023, 12, &InputSource::File(PathBuf::from("sample.txt")))(
+            crate::parse::ParseError::at(3, 7, "unknown spring condition `x`"),
+        );
+        assert_eq!(
+            err.to_string(),
+            "couldn't parse sample.txt at line 3, column 7: unknown spring condition `x`\ncheck that it's the whole input for 2023 day 12"
+        );
+    }
+
+    #[test]
+    fn test_mismatch_counts_failed_days() {
+        let store = PathBuf::from("answers/2023.toml");
+        let err = Error::Mismatch {
+            count: 2,
+            failed: 0,
+            store: store.clone(),
+        };
+        assert_eq!(err.to_string(), "2 answer(s) differ from answers/2023.toml");
+        let err = Error::Mismatch {
+            count: 0,
+            failed: 1,
+            store,
+        };
+        assert_eq!(
+            err.to_string(),
+            "0 answer(s) differ from answers/2023.toml and 1 day(s) failed to run"
+        );
+    }
+}
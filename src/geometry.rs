@@ -0,0 +1,223 @@
+use crate::grid::Coord;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+/// An offset or position that may be negative. `y` grows downwards, like lines of puzzle
+/// input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct Point {
+    pub(crate) x: isize,
+    pub(crate) y: isize,
+}
+
+/// A position that can't go negative, such as a tile of a [`Grid`](crate::grid::Grid).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct UPoint {
+    pub(crate) x: usize,
+    pub(crate) y: usize,
+}
+
+impl Point {
+    /// Distance from the origin moving only along rows and columns.
+    pub(crate) fn manhattan(self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    /// Distance from the origin when diagonal moves count as one step, like a king in chess.
+    pub(crate) fn chebyshev(self) -> usize {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+}
+
+impl UPoint {
+    pub(crate) fn manhattan(self, other: UPoint) -> usize {
+        (self - other).manhattan()
+    }
+
+    pub(crate) fn chebyshev(self, other: UPoint) -> usize {
+        (self - other).chebyshev()
+    }
+
+    /// `self` moved by `delta`, unless that leaves the rectangle from the origin up to but
+    /// not including `bounds`.
+    pub(crate) fn checked_add(self, delta: Point, bounds: UPoint) -> Option<UPoint> {
+        let x = self
+            .x
+            .checked_add_signed(delta.x)
+            .filter(|x| *x < bounds.x)?;
+        let y = self
+            .y
+            .checked_add_signed(delta.y)
+            .filter(|y| *y < bounds.y)?;
+        Some(UPoint { x, y })
+    }
+
+    /// The neighbour in `direction`, if it's inside `bounds`; see [`UPoint::checked_add`].
+    pub(crate) fn step(self, direction: Direction, bounds: UPoint) -> Option<UPoint> {
+        self.checked_add(direction.delta(), bounds)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        self + -other
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: isize) -> Point {
+        Point {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+/// The offset from `other` to `self`.
+impl Sub for UPoint {
+    type Output = Point;
+
+    fn sub(self, other: UPoint) -> Point {
+        Point::from(self) - Point::from(other)
+    }
+}
+
+impl From<UPoint> for Point {
+    fn from(point: UPoint) -> Self {
+        Point {
+            x: point.x as isize,
+            y: point.y as isize,
+        }
+    }
+}
+
+impl From<Coord> for UPoint {
+    fn from((row, col): Coord) -> Self {
+        UPoint { x: col, y: row }
+    }
+}
+
+impl From<UPoint> for Coord {
+    fn from(point: UPoint) -> Self {
+        (point.y, point.x)
+    }
+}
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl std::fmt::Display for UPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A compass direction on the map, north being up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Clockwise from north.
+    pub(crate) const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// A single step this way.
+    pub(crate) fn delta(self) -> Point {
+        match self {
+            Direction::North => Point { x: 0, y: -1 },
+            Direction::East => Point { x: 1, y: 0 },
+            Direction::South => Point { x: 0, y: 1 },
+            Direction::West => Point { x: -1, y: 0 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{Direction, Point, UPoint};
+
+    #[test]
+    fn test_distances() {
+        let a = UPoint { x: 4, y: 0 };
+        let b = UPoint { x: 9, y: 10 };
+        assert_eq!(a.manhattan(b), 15);
+        assert_eq!(b.manhattan(a), 15);
+        assert_eq!(a.chebyshev(b), 10);
+        assert_eq!(b - a, Point { x: 5, y: 10 });
+        assert_eq!(Point { x: -3, y: 2 }.manhattan(), 5);
+        assert_eq!(Point { x: -3, y: 2 }.chebyshev(), 3);
+    }
+
+    #[test]
+    fn test_operators() {
+        let mut p = Point { x: 1, y: -2 };
+        assert_eq!(p + Point { x: 2, y: 2 }, Point { x: 3, y: 0 });
+        assert_eq!(p - Point { x: 2, y: 2 }, Point { x: -1, y: -4 });
+        assert_eq!(-p, Point { x: -1, y: 2 });
+        assert_eq!(p * 3, Point { x: 3, y: -6 });
+        p += Direction::East.delta();
+        assert_eq!(p, Point { x: 2, y: -2 });
+    }
+
+    #[test]
+    fn test_checked_stepping() {
+        let bounds = UPoint { x: 3, y: 2 };
+        let corner = UPoint { x: 0, y: 0 };
+        assert_eq!(corner.step(Direction::North, bounds), None);
+        assert_eq!(corner.step(Direction::West, bounds), None);
+        assert_eq!(
+            corner.step(Direction::South, bounds),
+            Some(UPoint { x: 0, y: 1 })
+        );
+        assert_eq!(
+            corner.checked_add(Point { x: 2, y: 1 }, bounds),
+            Some(UPoint { x: 2, y: 1 })
+        );
+        assert_eq!(corner.checked_add(Point { x: 3, y: 0 }, bounds), None);
+        assert_eq!(UPoint::from((1, 2)), UPoint { x: 2, y: 1 });
+        assert_eq!(<(usize, usize)>::from(UPoint { x: 2, y: 1 }), (1, 2));
+    }
+}
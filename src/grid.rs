@@ -0,0 +1,280 @@
+use crate::{
+    geometry::UPoint,
+    parse::{self, ParseError},
+};
+use std::ops::{Index, IndexMut};
+
+/// A `(row, column)` position in a [`Grid`], counting from the top left.
+pub(crate) type Coord = (usize, usize);
+
+/// Up, down, left, right.
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Every surrounding tile, row by row.
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular map of tiles, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T: TryFrom<char, Error = ParseError>> Grid<T> {
+    /// One row per line and one tile per character. Ragged or empty grids are rejected, see
+    /// [`parse::rows`].
+    pub(crate) fn parse(input: &str) -> Result<Self, ParseError> {
+        let rows = parse::rows(input)?;
+        let cols = rows[0].len();
+        Ok(Self {
+            rows: rows.len(),
+            cols,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    pub(crate) fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub(crate) fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The corner just past the bottom right tile, for stepping with [`UPoint::step`].
+    pub(crate) fn bounds(&self) -> UPoint {
+        UPoint {
+            x: self.cols,
+            y: self.rows,
+        }
+    }
+
+    pub(crate) fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub(crate) fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells[col..].iter().step_by(self.cols)
+    }
+
+    /// Positions of the tiles matching `predicate`, row by row.
+    pub(crate) fn positions<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Coord> + 'a {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(index, _)| (index / self.cols, index % self.cols))
+    }
+
+    /// The position `delta` rows and columns away from `coord`, if it's on the grid.
+    pub(crate) fn offset(&self, (row, col): Coord, (rows, cols): (isize, isize)) -> Option<Coord> {
+        let row = row
+            .checked_add_signed(rows)
+            .filter(|row| *row < self.rows)?;
+        let col = col
+            .checked_add_signed(cols)
+            .filter(|col| *col < self.cols)?;
+        Some((row, col))
+    }
+
+    /// The tiles above, below, left and right of `coord` that are on the grid, in that order.
+    pub(crate) fn neighbours4(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |delta| self.offset(coord, *delta))
+    }
+
+    /// The tiles surrounding `coord`, diagonals included, that are on the grid.
+    pub(crate) fn neighbours8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        SURROUNDING
+            .iter()
+            .filter_map(move |delta| self.offset(coord, *delta))
+    }
+
+    /// Insert `row` so it ends up at index `at`, shifting later rows down.
+    pub(crate) fn insert_row(&mut self, at: usize, row: Vec<T>) {
+        assert_eq!(row.len(), self.cols, "row doesn't fit the grid");
+        let index = at * self.cols;
+        self.cells.splice(index..index, row);
+        self.rows += 1;
+    }
+
+    /// Insert `col` so it ends up at index `at`, shifting later columns right.
+    pub(crate) fn insert_col(&mut self, at: usize, col: Vec<T>) {
+        assert_eq!(col.len(), self.rows, "column doesn't fit the grid");
+        let mut old = std::mem::take(&mut self.cells).into_iter();
+        self.cols += 1;
+        for cell in col {
+            self.cells.extend(old.by_ref().take(at));
+            self.cells.push(cell);
+            self.cells.extend(old.by_ref().take(self.cols - at - 1));
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Mirror along the main diagonal, so rows become columns.
+    pub(crate) fn transpose(&self) -> Self {
+        Self {
+            cells: (0..self.cols)
+                .flat_map(|col| self.column(col).cloned())
+                .collect(),
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+
+    /// Rotate a quarter turn clockwise, so the left column becomes the top row.
+    pub(crate) fn rotate_right(&self) -> Self {
+        Self {
+            cells: (0..self.cols)
+                .flat_map(|col| {
+                    (0..self.rows)
+                        .rev()
+                        .map(move |row| self[(row, col)].clone())
+                })
+                .collect(),
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): Coord) -> &T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({row}, {col}) is off the grid"
+        );
+        &self.cells[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, (row, col): Coord) -> &mut T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({row}, {col}) is off the grid"
+        );
+        &mut self.cells[row * self.cols + col]
+    }
+}
+
+/// Rows on separate lines without a trailing newline, the inverse of [`Grid::parse`] when
+/// tiles display as the character they were parsed from.
+impl<T: std::fmt::Display> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(row) {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+    use crate::parse::ParseError;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Tile(char);
+
+    impl TryFrom<char> for Tile {
+        type Error = ParseError;
+
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                'a'..='z' | '.' => Ok(Tile(c)),
+                _ => Err(ParseError::new(format!("unknown tile `{c}`"))),
+            }
+        }
+    }
+
+    impl std::fmt::Display for Tile {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    const SAMPLE: &str = "abc\ndef";
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid: Grid<Tile> = Grid::parse(SAMPLE).unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid[(1, 0)], Tile('d'));
+        assert_eq!(grid.to_string(), SAMPLE);
+        assert_eq!(Grid::parse(&grid.to_string()), Ok(grid));
+
+        assert_eq!(
+            Grid::<Tile>::parse("abc\ndXf").unwrap_err(),
+            ParseError::at(2, 2, "unknown tile `X`")
+        );
+        assert!(Grid::<Tile>::parse("abc\nde").is_err());
+    }
+
+    #[test]
+    fn test_views() {
+        let grid: Grid<Tile> = Grid::parse(SAMPLE).unwrap();
+        assert_eq!(grid.row(1), [Tile('d'), Tile('e'), Tile('f')]);
+        assert_eq!(grid.column(2).collect::<Vec<_>>(), [&Tile('c'), &Tile('f')]);
+        assert_eq!(
+            grid.positions(|tile| tile.0 > 'c').collect::<Vec<_>>(),
+            [(1, 0), (1, 1), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid: Grid<Tile> = Grid::parse("...\n...\n...").unwrap();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbours8((2, 2)).collect::<Vec<_>>(),
+            [(1, 1), (1, 2), (2, 1)]
+        );
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.offset((2, 0), (0, 2)), Some((2, 2)));
+        assert_eq!(grid.offset((2, 0), (1, 0)), None);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid: Grid<Tile> = Grid::parse(SAMPLE).unwrap();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_right().to_string(), "da\neb\nfc");
+        let full_turn = (0..4).fold(grid.clone(), |grid, _| grid.rotate_right());
+        assert_eq!(full_turn, grid);
+
+        let mut grid = grid;
+        grid.insert_row(1, vec![Tile('x'); 3]);
+        grid.insert_col(3, vec![Tile('y'); 3]);
+        grid.insert_col(0, vec![Tile('z'); 3]);
+        assert_eq!(grid.to_string(), "zabcy\nzxxxy\nzdefy");
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+pub(crate) const DEFAULT_INPUT_DIR: &str = "inputs";
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum InputSource {
+    /// `<dir>/<year>/dayNN.txt`, as written by `bootstrap`
+    Dir(PathBuf),
+    File(PathBuf),
+    Stdin,
+}
+
+impl Default for InputSource {
+    /// The repo's own `inputs/` directory, which the day tests read from.
+    fn default() -> Self {
+        InputSource::Dir(PathBuf::from(DEFAULT_INPUT_DIR))
+    }
+}
+
+impl InputSource {
+    /// The `--input` argument if given, otherwise the day's file in `dir`.
+    pub(crate) fn new(arg: Option<PathBuf>, dir: &Path) -> Self {
+        match arg {
+            None => InputSource::Dir(dir.to_owned()),
+            Some(path) if path == Path::new("-") => InputSource::Stdin,
+            Some(path) => InputSource::File(path),
+        }
+    }
+}
+
+pub(crate) fn path(dir: &Path, year: u16, day: usize) -> PathBuf {
+    dir.join(format!("{year}/day{day:02}.txt"))
+}
+
+pub(crate) fn load(year: u16, day: usize, source: &InputSource) -> std::io::Result<String> {
+    match source {
+        InputSource::Dir(dir) => std::fs::read_to_string(path(dir, year, day)),
+        InputSource::File(path) => std::fs::read_to_string(path),
+        InputSource::Stdin => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{load, path, InputSource};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_source_from_arg() {
+        let dir = Path::new("data");
+        assert_eq!(
+            InputSource::new(None, dir),
+            InputSource::Dir(PathBuf::from("data"))
+        );
+        assert_eq!(
+            InputSource::new(Some(PathBuf::from("-")), dir),
+            InputSource::Stdin
+        );
+        assert_eq!(
+            InputSource::new(Some(PathBuf::from("sample.txt")), dir),
+            InputSource::File(PathBuf::from("sample.txt"))
+        );
+    }
+
+    #[test]
+    fn test_load_default_and_file() {
+        assert_eq!(
+            path(Path::new("inputs"), 2023, 5),
+            PathBuf::from("inputs/2023/day05.txt")
+        );
+        let default = load(2023, 5, &InputSource::default()).unwrap();
+        let file = load(
+            2023,
+            1,
+            &InputSource::File(path(Path::new("inputs"), 2023, 5)),
+        )
+        .unwrap();
+        assert!(default.starts_with("seeds:"));
+        assert_eq!(default, file);
+        assert!(load(1999, 1, &InputSource::default()).is_err());
+    }
+}
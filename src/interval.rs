@@ -0,0 +1,190 @@
+/// The half-open range `start..end`, empty when `start >= end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct Interval {
+    pub(crate) start: i64,
+    pub(crate) end: i64,
+}
+
+impl Interval {
+    pub(crate) fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    /// The `len` values from `start` onwards, how puzzles usually describe a range.
+    pub(crate) fn with_len(start: i64, len: i64) -> Self {
+        Self::new(start, start + len)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// The values below `at` and the rest, either of which may be empty.
+    pub(crate) fn split_at(self, at: i64) -> (Interval, Interval) {
+        let at = at.clamp(self.start, self.end.max(self.start));
+        (Self::new(self.start, at), Self::new(at, self.end))
+    }
+
+    /// The values in both, which may be empty.
+    pub(crate) fn intersect(self, other: Interval) -> Interval {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The values not in `other`: the pieces before and after it, either of which may be
+    /// empty.
+    pub(crate) fn difference(self, other: Interval) -> [Interval; 2] {
+        let (before, rest) = self.split_at(other.start);
+        let (_, after) = rest.split_at(other.end);
+        [before, after]
+    }
+
+    /// Every value moved by `by`.
+    pub(crate) fn shift(self, by: i64) -> Interval {
+        Self::new(self.start + by, self.end + by)
+    }
+}
+
+/// A set of values stored as sorted intervals that neither overlap nor touch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut sorted: Vec<Interval> = iter.into_iter().filter(|i| !i.is_empty()).collect();
+        sorted.sort();
+        let mut intervals: Vec<Interval> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => intervals.push(interval),
+            }
+        }
+        Self { intervals }
+    }
+}
+
+impl IntervalSet {
+    pub(crate) fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub(crate) fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub(crate) fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub(crate) fn difference(&self, other: Interval) -> IntervalSet {
+        self.intervals
+            .iter()
+            .flat_map(|interval| interval.difference(other))
+            .collect()
+    }
+}
+
+/// A piecewise function that shifts each source interval by its own offset and leaves values
+/// outside all of them alone. Where sources overlap the first one wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct OffsetMap {
+    pieces: Vec<(Interval, i64)>,
+}
+
+impl FromIterator<(Interval, i64)> for OffsetMap {
+    fn from_iter<I: IntoIterator<Item = (Interval, i64)>>(iter: I) -> Self {
+        Self {
+            pieces: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl OffsetMap {
+    /// Where every value in `input` ends up, splitting its intervals wherever a piece of the
+    /// map starts or ends.
+    pub(crate) fn map(&self, input: &IntervalSet) -> IntervalSet {
+        let mut unmapped = input.clone();
+        let mut mapped = vec![];
+        for (source, offset) in &self.pieces {
+            mapped.extend(
+                unmapped
+                    .intervals()
+                    .iter()
+                    .map(|interval| interval.intersect(*source).shift(*offset)),
+            );
+            unmapped = unmapped.difference(*source);
+        }
+        unmapped.union(&mapped.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interval::{Interval, IntervalSet, OffsetMap};
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval_arithmetic() {
+        let a = Interval::new(2, 8);
+        assert_eq!(a.split_at(5), (Interval::new(2, 5), Interval::new(5, 8)));
+        assert!(a.split_at(0).0.is_empty());
+        assert_eq!(a.split_at(10), (a, Interval::new(8, 8)));
+        assert_eq!(a.intersect(Interval::new(6, 20)), Interval::new(6, 8));
+        assert!(a.intersect(Interval::new(8, 20)).is_empty());
+        assert_eq!(
+            a.difference(Interval::new(4, 6)),
+            [Interval::new(2, 4), Interval::new(6, 8)]
+        );
+        assert!(a
+            .difference(Interval::new(0, 10))
+            .iter()
+            .all(Interval::is_empty));
+        assert_eq!(a.shift(-2), Interval::new(0, 6));
+        assert_eq!(Interval::with_len(79, 14), Interval::new(79, 93));
+    }
+
+    #[test]
+    fn test_sets_are_normalised() {
+        // overlapping and touching intervals merge, empty ones vanish
+        assert_eq!(
+            set(&[(5, 7), (0, 2), (1, 3), (3, 4), (9, 9)]),
+            set(&[(0, 4), (5, 7)])
+        );
+        assert_eq!(
+            set(&[(0, 2)]).union(&set(&[(2, 5), (8, 9)])),
+            set(&[(0, 5), (8, 9)])
+        );
+        assert_eq!(
+            set(&[(0, 5), (8, 12)]).difference(Interval::new(3, 10)),
+            set(&[(0, 3), (10, 12)])
+        );
+        assert_eq!(set(&[(8, 12), (3, 4)]).min(), Some(3));
+        assert_eq!(IntervalSet::default().min(), None);
+    }
+
+    #[test]
+    fn test_offset_map() {
+        // 50..98 moves up two, 98..100 moves down to 50..52
+        let map: OffsetMap = [(Interval::new(98, 100), -48), (Interval::new(50, 98), 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(map.map(&set(&[(79, 93)])), set(&[(81, 95)]));
+        assert_eq!(
+            map.map(&set(&[(40, 55), (97, 102)])),
+            set(&[(40, 57), (99, 102)])
+        );
+        assert_eq!(map.map(&set(&[(10, 20)])), set(&[(10, 20)]));
+    }
+}
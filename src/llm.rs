@@ -0,0 +1,577 @@
+use async_openai::{
+    config::OpenAIConfig,
+    types::{
+        ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+        ChatCompletionResponseFormat, ChatCompletionResponseFormatType,
+        CreateChatCompletionRequestArgs,
+    },
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+pub(crate) const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+pub(crate) const DEFAULT_MODEL: &str = "gpt-4-1106-preview";
+pub(crate) const DEFAULT_MAX_TOKENS: u16 = 1024;
+pub(crate) const DEFAULT_CACHE_DIR: &str = ".llm-cache";
+
+#[derive(Debug)]
+pub(crate) enum LlmError {
+    Request(String),
+    EmptyResponse,
+    Fixture(PathBuf, std::io::Error),
+    MissingFixture,
+    Cache(PathBuf, std::io::Error),
+    CacheMiss(String),
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LlmError::Request(message) => write!(f, "completion request failed: {message}"),
+            LlmError::EmptyResponse => write!(f, "the model returned no content"),
+            LlmError::Fixture(path, err) => {
+                write!(f, "couldn't read fixture {}: {err}", path.display())
+            }
+            LlmError::MissingFixture => {
+                write!(
+                    f,
+                    "the fixture backend needs --llm-fixture or AOC_LLM_FIXTURE"
+                )
+            }
+            LlmError::Cache(path, err) => {
+                write!(f, "couldn't use cached response {}: {err}", path.display())
+            }
+            LlmError::CacheMiss(key) => {
+                write!(
+                    f,
+                    "no cached response for prompt {key} and replay mode is on"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+/// One chat message. Few-shot examples are sent as system messages named
+/// `example_user`/`example_assistant`, following OpenAI's prompting guide.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Message {
+    pub(crate) role: Role,
+    pub(crate) name: Option<&'static str>,
+    pub(crate) content: String,
+}
+
+impl Message {
+    pub(crate) fn system(content: &str) -> Self {
+        Self {
+            role: Role::System,
+            name: None,
+            content: content.to_owned(),
+        }
+    }
+
+    pub(crate) fn example(name: &'static str, content: &str) -> Self {
+        Self {
+            role: Role::System,
+            name: Some(name),
+            content: content.to_owned(),
+        }
+    }
+
+    pub(crate) fn user(content: &str) -> Self {
+        Self {
+            role: Role::User,
+            name: None,
+            content: content.to_owned(),
+        }
+    }
+
+    pub(crate) fn assistant(content: &str) -> Self {
+        Self {
+            role: Role::Assistant,
+            name: None,
+            content: content.to_owned(),
+        }
+    }
+}
+
+/// Something that can answer a chat prompt with a JSON object.
+pub(crate) trait LlmBackend {
+    fn complete(&self, messages: &[Message]) -> Result<String, LlmError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub(crate) enum BackendKind {
+    /// Any server speaking the OpenAI chat completions API
+    #[value(name = "openai")]
+    OpenAi,
+    /// Replay canned responses from disk
+    Fixture,
+}
+
+/// Everything needed to build a backend.
+#[derive(Debug, Clone)]
+pub(crate) struct LlmConfig {
+    pub(crate) kind: BackendKind,
+    pub(crate) base_url: String,
+    pub(crate) model: String,
+    pub(crate) max_tokens: u16,
+    pub(crate) fixture: Option<PathBuf>,
+    pub(crate) cache_dir: PathBuf,
+    pub(crate) cache_mode: CacheMode,
+}
+
+/// Real requests go through the response cache. Fixture responses don't, since they would
+/// be stored under the configured model's name; replay mode never builds a backend at all.
+pub(crate) fn backend(config: &LlmConfig) -> Result<Box<dyn LlmBackend>, LlmError> {
+    if config.cache_mode == CacheMode::Replay {
+        return Ok(Box::new(CachedBackend::replay(config)));
+    }
+    match config.kind {
+        BackendKind::OpenAi => Ok(Box::new(CachedBackend::new(
+            Box::new(OpenAiBackend::new(
+                &config.base_url,
+                &config.model,
+                config.max_tokens,
+            )),
+            config,
+        ))),
+        BackendKind::Fixture => {
+            let path = config.fixture.as_ref().ok_or(LlmError::MissingFixture)?;
+            Ok(Box::new(FixtureBackend::load(path)?))
+        }
+    }
+}
+
+/// Chat completions against OpenAI or a compatible local server such as llama.cpp or Ollama.
+/// The API key comes from `OPENAI_API_KEY` and may be empty for local servers.
+pub(crate) struct OpenAiBackend {
+    client: Client<OpenAIConfig>,
+    model: String,
+    max_tokens: u16,
+}
+
+impl OpenAiBackend {
+    pub(crate) fn new(base_url: &str, model: &str, max_tokens: u16) -> Self {
+        Self {
+            client: Client::with_config(
+                OpenAIConfig::new().with_api_base(base_url.trim_end_matches('/')),
+            ),
+            model: model.to_owned(),
+            max_tokens,
+        }
+    }
+}
+
+impl LlmBackend for OpenAiBackend {
+    fn complete(&self, messages: &[Message]) -> Result<String, LlmError> {
+        let messages = messages
+            .iter()
+            .map(|message| -> Result<ChatCompletionRequestMessage, _> {
+                match message.role {
+                    Role::System => {
+                        let mut args = ChatCompletionRequestSystemMessageArgs::default();
+                        args.content(message.content.clone());
+                        if let Some(name) = message.name {
+                            args.name(name);
+                        }
+                        args.build().map(Into::into)
+                    }
+                    Role::User => ChatCompletionRequestUserMessageArgs::default()
+                        .content(message.content.clone())
+                        .build()
+                        .map(Into::into),
+                    Role::Assistant => ChatCompletionRequestAssistantMessageArgs::default()
+                        .content(message.content.clone())
+                        .build()
+                        .map(Into::into),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| LlmError::Request(e.to_string()))?;
+        let request = CreateChatCompletionRequestArgs::default()
+            .max_tokens(self.max_tokens)
+            .model(&self.model)
+            .response_format(ChatCompletionResponseFormat {
+                r#type: ChatCompletionResponseFormatType::JsonObject,
+            })
+            .messages(messages)
+            .build()
+            .map_err(|e| LlmError::Request(e.to_string()))?;
+
+        // the rest of the tool is blocking, so run just this request on its own runtime
+        let response = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .map_err(|e| LlmError::Request(e.to_string()))?
+            .block_on(self.client.chat().create(request))
+            .map_err(|e| LlmError::Request(e.to_string()))?;
+
+        response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .ok_or(LlmError::EmptyResponse)
+    }
+}
+
+/// Deterministic backend that ignores the prompt and returns canned responses in order,
+/// repeating the last one. A fixture file holds a single response; a directory holds one
+/// response per file, taken in file name order.
+pub(crate) struct FixtureBackend {
+    responses: Vec<String>,
+    next: AtomicUsize,
+}
+
+impl FixtureBackend {
+    pub(crate) fn new(responses: Vec<String>) -> Self {
+        assert!(!responses.is_empty(), "need at least one fixture response");
+        Self {
+            responses,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn load(path: &Path) -> Result<Self, LlmError> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path).map_err(|e| LlmError::Fixture(path.to_owned(), e))
+        };
+        let responses = if path.is_dir() {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+                .map_err(|e| LlmError::Fixture(path.to_owned(), e))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.is_file())
+                .collect();
+            files.sort();
+            files
+                .iter()
+                .map(|p| read(p))
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![read(path)?]
+        };
+        if responses.is_empty() {
+            return Err(LlmError::Fixture(
+                path.to_owned(),
+                std::io::Error::new(std::io::ErrorKind::NotFound, "no fixture files"),
+            ));
+        }
+        Ok(Self::new(responses))
+    }
+}
+
+impl LlmBackend for FixtureBackend {
+    fn complete(&self, _messages: &[Message]) -> Result<String, LlmError> {
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        Ok(self.responses[index.min(self.responses.len() - 1)].clone())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CacheMode {
+    /// Answer from the cache when possible and store new responses
+    ReadWrite,
+    /// Always ask the backend, overwriting whatever was cached
+    Refresh,
+    /// Only answer from the cache; a miss is an error
+    Replay,
+}
+
+/// A cached completion, stored as `<key>.json` in the cache directory.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    model: String,
+    response: String,
+}
+
+/// Content-addressed store of completions. The key hashes the server, model name, token
+/// limit and every message, so a changed puzzle, few-shot library or retry prompt is a
+/// different entry, and so is a retry allowed a longer reply.
+pub(crate) struct CachedBackend {
+    inner: Option<Box<dyn LlmBackend>>,
+    dir: PathBuf,
+    base_url: String,
+    model: String,
+    max_tokens: u16,
+    mode: CacheMode,
+}
+
+impl CachedBackend {
+    /// Cache `inner`'s responses in `config.cache_dir` according to `config.cache_mode`.
+    pub(crate) fn new(inner: Box<dyn LlmBackend>, config: &LlmConfig) -> Self {
+        Self {
+            inner: Some(inner),
+            dir: config.cache_dir.clone(),
+            base_url: config.base_url.clone(),
+            model: config.model.clone(),
+            max_tokens: config.max_tokens,
+            mode: config.cache_mode,
+        }
+    }
+
+    /// Answer only from the responses cached for `config`.
+    pub(crate) fn replay(config: &LlmConfig) -> Self {
+        Self {
+            inner: None,
+            dir: config.cache_dir.clone(),
+            base_url: config.base_url.clone(),
+            model: config.model.clone(),
+            max_tokens: config.max_tokens,
+            mode: CacheMode::Replay,
+        }
+    }
+
+    pub(crate) fn key(&self, messages: &[Message]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.base_url.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.model.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.max_tokens.to_le_bytes());
+        for message in messages {
+            // separators keep ("ab", "c") and ("a", "bc") apart
+            hasher.update([0]);
+            hasher.update(format!("{:?}", message.role).as_bytes());
+            hasher.update([0]);
+            hasher.update(message.name.unwrap_or_default().as_bytes());
+            hasher.update([0]);
+            hasher.update(message.content.as_bytes());
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<String>, LlmError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(LlmError::Cache(path.to_owned(), e)),
+        };
+        let entry: CacheEntry = serde_json::from_str(&content).map_err(|e| {
+            LlmError::Cache(
+                path.to_owned(),
+                std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            )
+        })?;
+        Ok(Some(entry.response))
+    }
+
+    fn write(&self, path: &Path, response: &str) -> Result<(), LlmError> {
+        let entry = CacheEntry {
+            model: self.model.clone(),
+            response: response.to_owned(),
+        };
+        std::fs::create_dir_all(&self.dir)
+            .and_then(|()| {
+                std::fs::write(
+                    path,
+                    serde_json::to_string_pretty(&entry).expect("cache entries serialize"),
+                )
+            })
+            .map_err(|e| LlmError::Cache(path.to_owned(), e))
+    }
+}
+
+impl LlmBackend for CachedBackend {
+    fn complete(&self, messages: &[Message]) -> Result<String, LlmError> {
+        let key = self.key(messages);
+        let path = self.dir.join(format!("{key}.json"));
+        if self.mode != CacheMode::Refresh {
+            if let Some(response) = self.read(&path)? {
+                return Ok(response);
+            }
+        }
+        let Some(inner) = self
+            .inner
+            .as_ref()
+            .filter(|_| self.mode != CacheMode::Replay)
+        else {
+            return Err(LlmError::CacheMiss(key));
+        };
+        let response = inner.complete(messages)?;
+        self.write(&path, &response)?;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::llm::{
+        backend, BackendKind, CacheMode, CachedBackend, FixtureBackend, LlmBackend, LlmConfig,
+        LlmError, Message,
+    };
+    use std::path::{Path, PathBuf};
+
+    fn fixture_config(fixture: Option<PathBuf>) -> LlmConfig {
+        LlmConfig {
+            kind: BackendKind::Fixture,
+            base_url: String::new(),
+            model: String::new(),
+            max_tokens: 0,
+            fixture,
+            cache_dir: PathBuf::new(),
+            cache_mode: CacheMode::ReadWrite,
+        }
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn cache_config(cache_dir: &Path, model: &str, cache_mode: CacheMode) -> LlmConfig {
+        LlmConfig {
+            kind: BackendKind::OpenAi,
+            base_url: "http://localhost:11434/v1".to_owned(),
+            model: model.to_owned(),
+            max_tokens: 1024,
+            fixture: None,
+            cache_dir: cache_dir.to_owned(),
+            cache_mode,
+        }
+    }
+
+    #[test]
+    fn test_fixture_responses_in_order() {
+        let backend = FixtureBackend::new(vec!["first".to_owned(), "second".to_owned()]);
+        let prompt = [Message::user("anything")];
+
+        assert_eq!(backend.complete(&prompt).unwrap(), "first");
+        assert_eq!(backend.complete(&prompt).unwrap(), "second");
+        assert_eq!(backend.complete(&prompt).unwrap(), "second");
+    }
+
+    #[test]
+    fn test_fixture_directory() {
+        let dir = std::env::temp_dir().join("aoc-llm-fixture-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("2.json"), "{\"rust_code\": \"b\"}").unwrap();
+        std::fs::write(dir.join("1.json"), "{\"rust_code\": \"a\"}").unwrap();
+
+        let backend = backend(&fixture_config(Some(dir))).unwrap();
+        assert_eq!(backend.complete(&[]).unwrap(), "{\"rust_code\": \"a\"}");
+        assert_eq!(backend.complete(&[]).unwrap(), "{\"rust_code\": \"b\"}");
+    }
+
+    #[test]
+    fn test_fixture_needs_a_path() {
+        assert!(matches!(
+            backend(&fixture_config(None)),
+            Err(LlmError::MissingFixture)
+        ));
+        assert!(matches!(
+            backend(&fixture_config(Some("does/not/exist.json".into()))),
+            Err(LlmError::Fixture(..))
+        ));
+    }
+
+    #[test]
+    fn test_cache_stores_and_reuses_responses() {
+        let dir = cache_dir("aoc-llm-cache-test");
+        let fixture = || Box::new(FixtureBackend::new(vec!["first".into(), "second".into()]));
+        let config = cache_config(&dir, "model", CacheMode::ReadWrite);
+        let cached = CachedBackend::new(fixture(), &config);
+        let puzzle = [Message::user("puzzle one")];
+
+        assert_eq!(cached.complete(&puzzle).unwrap(), "first");
+        assert_eq!(cached.complete(&puzzle).unwrap(), "first");
+        assert_eq!(
+            cached.complete(&[Message::user("puzzle two")]).unwrap(),
+            "second"
+        );
+
+        // the same prompt to another model is a different entry
+        let other = CachedBackend::new(
+            fixture(),
+            &cache_config(&dir, "other", CacheMode::ReadWrite),
+        );
+        assert_ne!(other.key(&puzzle), cached.key(&puzzle));
+        assert_eq!(other.complete(&puzzle).unwrap(), "first");
+
+        // and so is the same model on another server, or allowed a longer reply
+        let elsewhere = LlmConfig {
+            base_url: "https://api.openai.com/v1".to_owned(),
+            ..config.clone()
+        };
+        assert_ne!(
+            CachedBackend::new(fixture(), &elsewhere).key(&puzzle),
+            cached.key(&puzzle)
+        );
+        let longer = LlmConfig {
+            max_tokens: 4096,
+            ..config.clone()
+        };
+        let longer = CachedBackend::new(fixture(), &longer);
+        assert_ne!(longer.key(&puzzle), cached.key(&puzzle));
+        assert_eq!(longer.complete(&puzzle).unwrap(), "first");
+        assert_eq!(
+            longer.complete(&[Message::user("puzzle two")]).unwrap(),
+            "second"
+        );
+
+        let replay = CachedBackend::replay(&config);
+        assert_eq!(replay.complete(&puzzle).unwrap(), "first");
+        assert!(matches!(
+            replay.complete(&[Message::user("puzzle three")]),
+            Err(LlmError::CacheMiss(_))
+        ));
+    }
+
+    #[test]
+    fn test_cache_refresh() {
+        let dir = cache_dir("aoc-llm-cache-refresh-test");
+        let puzzle = [Message::user("puzzle")];
+        let stale = FixtureBackend::new(vec!["stale".into()]);
+        let config = cache_config(&dir, "model", CacheMode::ReadWrite);
+        CachedBackend::new(Box::new(stale), &config)
+            .complete(&puzzle)
+            .unwrap();
+
+        let fresh = FixtureBackend::new(vec!["fresh".into()]);
+        let refreshed = CachedBackend::new(
+            Box::new(fresh),
+            &LlmConfig {
+                cache_mode: CacheMode::Refresh,
+                ..config.clone()
+            },
+        );
+        assert_eq!(refreshed.complete(&puzzle).unwrap(), "fresh");
+        assert_eq!(
+            CachedBackend::replay(&config).complete(&puzzle).unwrap(),
+            "fresh"
+        );
+    }
+
+    #[test]
+    fn test_replay_mode_needs_no_backend() {
+        let config = LlmConfig {
+            kind: BackendKind::OpenAi,
+            cache_dir: cache_dir("aoc-llm-cache-replay-test"),
+            cache_mode: CacheMode::Replay,
+            ..fixture_config(None)
+        };
+        let backend = backend(&config).unwrap();
+        assert!(matches!(
+            backend.complete(&[Message::user("puzzle")]),
+            Err(LlmError::CacheMiss(_))
+        ));
+    }
+}
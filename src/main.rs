@@ -1,19 +1,36 @@
-use clap::{Parser, Subcommand};
-use serde_json::json;
-use std::{fs::File, io::Read, path::Path, process::Command};
-
-use async_openai::{
-    types::{
-        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-        ChatCompletionResponseFormat, ChatCompletionResponseFormatType,
-        CreateChatCompletionRequestArgs,
-    },
-    Client,
+use answer_store::{AnswerStore, Check};
+use clap::{Args, Parser, Subcommand};
+use client::{AocClient, Verdict};
+use config::Config;
+use error::Error;
+use input::InputSource;
+use llm::{CacheMode, LlmBackend, LlmConfig};
+use puzzle::Puzzle;
+use scaffold::Templates;
+use solution::{Registry, Solution};
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::Command,
 };
+use submissions::{Attempt, SubmissionLog};
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 struct Cli {
+    /// Puzzle year, defaults to `year` in aoc.toml or the most recent Advent of Code event
+    #[arg(short, long, global = true)]
+    year: Option<u16>,
+
+    /// Read puzzle input from this file instead of `<input_dir>/<year>/dayNN.txt`, `-` for stdin
+    #[arg(short, long, global = true)]
+    input: Option<PathBuf>,
+
+    /// Settings file, defaults to `aoc.toml` in the current directory if it exists
+    #[arg(long, global = true, env = "AOC_CONFIG")]
+    config: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -22,244 +39,599 @@ struct Cli {
 enum Commands {
     Bootstrap {
         day: usize,
+        /// Directory holding `day.rs.hbs` and `year_mod.rs.hbs` overrides, defaults to
+        /// `template_dir` in aoc.toml
+        #[arg(long)]
+        template_dir: Option<PathBuf>,
+        #[command(flatten)]
+        llm: LlmArgs,
     },
     #[command(alias = "print")]
-    PrintSolution {
-        day: usize,
-    },
+    PrintSolution { day: usize },
     Submit {
         day: usize,
+        #[arg(value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=2))]
         part: usize,
+        /// Sleep through an active cooldown instead of refusing to submit
+        #[arg(long)]
+        wait: bool,
+        /// Submit even if the answer was already rejected or is outside known bounds
+        #[arg(long)]
+        force: bool,
     },
     TestCompletion {
         day: usize,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+    /// Re-download a bootstrapped day's puzzle and add tests for any new part-two examples
+    RefreshTests {
+        day: usize,
+        #[command(flatten)]
+        llm: LlmArgs,
+    },
+    /// Run and time every implemented day of the year using the default inputs
+    #[command(alias = "all")]
+    RunAll {
+        /// Days to leave out, e.g. `--skip 5,12`
+        #[arg(long, value_delimiter = ',')]
+        skip: Vec<usize>,
+    },
+    /// Benchmark both parts of a day over repeated runs
+    Bench {
+        day: usize,
+        /// Untimed runs before measuring
+        #[arg(long, default_value_t = 3)]
+        warmup: usize,
+        /// Timed runs per part
+        #[arg(long, default_value_t = 20)]
+        runs: usize,
+        /// Write the results to this JSON file
+        #[arg(long)]
+        save_baseline: Option<PathBuf>,
+        /// Compare the results against a previously saved JSON file
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Percentage change in mean time that counts as a regression
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Re-run every day with recorded answers and report any that changed, listing the days
+    /// without any
+    Verify {
+        /// Days to leave out, e.g. `--skip 5,12`
+        #[arg(long, value_delimiter = ',')]
+        skip: Vec<usize>,
     },
+    /// List implemented and missing days
+    List,
+    /// Read downloaded puzzle descriptions
+    Puzzle {
+        #[command(subcommand)]
+        command: PuzzleCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum PuzzleCommands {
+    /// Print a day's puzzle with its accepted and recorded answers
+    Show { day: usize },
+}
+
+// Which model generates sample tests and where to reach it. Kept as a plain comment
+// because a doc comment here would replace the help text of every command flattening it.
+#[derive(Args)]
+struct LlmArgs {
+    /// Backend used to generate sample tests
+    #[arg(
+        long = "llm",
+        value_enum,
+        env = "AOC_LLM_BACKEND",
+        default_value = "openai"
+    )]
+    kind: llm::BackendKind,
+    /// Base URL of an OpenAI-compatible API, e.g. `http://localhost:11434/v1` for Ollama;
+    /// defaults to `llm.base_url` in aoc.toml
+    #[arg(long = "llm-base-url")]
+    base_url: Option<String>,
+    /// Model name passed to the API, defaults to `llm.model` in aoc.toml
+    #[arg(long = "llm-model")]
+    model: Option<String>,
+    /// Upper bound on the length of each completion
+    #[arg(long = "llm-max-tokens", env = "AOC_LLM_MAX_TOKENS", default_value_t = llm::DEFAULT_MAX_TOKENS)]
+    max_tokens: u16,
+    /// Directory of few-shot example files shown to the model
+    #[arg(long = "few-shot-dir", env = "AOC_FEW_SHOT_DIR", default_value = sample_tests::DEFAULT_FEW_SHOT_DIR)]
+    few_shot_dir: PathBuf,
+    /// Requests to make before giving up on getting tests that pass validation
+    #[arg(long = "llm-attempts", default_value_t = sample_tests::DEFAULT_ATTEMPTS)]
+    attempts: usize,
+    /// Response file, or directory of response files, replayed by the fixture backend
+    #[arg(long = "llm-fixture", env = "AOC_LLM_FIXTURE")]
+    fixture: Option<PathBuf>,
+    /// Directory of cached responses, keyed by a hash of the server, model, token limit and
+    /// prompt
+    #[arg(long = "llm-cache-dir", env = "AOC_LLM_CACHE_DIR", default_value = llm::DEFAULT_CACHE_DIR)]
+    cache_dir: PathBuf,
+    /// Ask the model again even if a cached response exists
+    #[arg(long, conflicts_with = "replay")]
+    refresh: bool,
+    /// Only use cached responses and fail on a miss, so generation runs offline
+    #[arg(long, env = "AOC_LLM_REPLAY")]
+    replay: bool,
 }
 
-const CURRENT_YEAR: u16 = 2024;
+impl LlmArgs {
+    fn backend(&self, config: &Config) -> Result<Box<dyn LlmBackend>, Error> {
+        llm::backend(&LlmConfig {
+            kind: self.kind,
+            base_url: self.base_url.clone().unwrap_or(config.llm.base_url.clone()),
+            model: self.model.clone().unwrap_or(config.llm.model.clone()),
+            max_tokens: self.max_tokens,
+            fixture: self.fixture.clone(),
+            cache_dir: self.cache_dir.clone(),
+            cache_mode: match (self.refresh, self.replay) {
+                (true, _) => CacheMode::Refresh,
+                (_, true) => CacheMode::Replay,
+                _ => CacheMode::ReadWrite,
+            },
+        })
+        .map_err(Error::from)
+    }
+}
 
 fn main() {
     let cli = Cli::parse();
+    if let Err(err) = run(&cli) {
+        eprintln!("{err}");
+        std::process::exit(err.exit_code());
+    }
+}
+
+fn run(cli: &Cli) -> Result<(), Error> {
+    let config = Config::load(cli.config.as_deref(), |name| std::env::var(name).ok())?;
+    let registry = Registry::new();
+    let year = cli.year.unwrap_or_else(|| config.year());
+    let input_source = InputSource::new(cli.input.clone(), &config.input_dir);
 
     // You can check for the existence of subcommands, and if found use their
     // matches just as you would the top level cmd
     match &cli.command {
-        Commands::Bootstrap { day } => {
-            println!("bootstrapping day {day}!");
-            let formatted_day = format!("{day:02}");
-            Command::new("aoc")
-                .args([
-                    "--day",
-                    &day.to_string(),
-                    "--overwrite",
-                    "--input-file",
-                    &format!("inputs/{CURRENT_YEAR}/day{formatted_day}.txt"),
-                    "--puzzle-file",
-                    &format!("puzzles/{CURRENT_YEAR}/day{formatted_day}.md"),
-                    "download",
-                ])
-                .output()
-                .expect("failed to execute process");
-            let mut hb = handlebars::Handlebars::new();
-            hb.register_template_string("day_tmpl", DAY_FILE)
-                .expect("Invalid template");
-
-            // using async for just this bit
-            tokio::runtime::Builder::new_current_thread()
-                .enable_io()
-                .enable_time()
-                .build()
-                .unwrap()
-                .block_on(async {
-                    let day_rs_name = format!("src/day{formatted_day}.rs");
-                    let rs_path = Path::new(&day_rs_name);
-                    if !rs_path.exists() {
-                        println!("generating day {day} tests!");
-                        let test_case = generate_sample_test_case(*day).await;
-                        let mut output_file = File::create(rs_path).expect("Could not open file");
-                        hb.render_to_write(
-                            "day_tmpl",
-                            &json!({"formatted_day": formatted_day, "test_case": test_case.rust_code}),
-                            &mut output_file,
-                        )
-                        .expect("Could not write template");
-                    }
-                });
-            Command::new("cargo")
-                .args(["fmt"])
-                .status()
-                .expect("expected fmt to work");
+        Commands::Bootstrap {
+            day,
+            template_dir,
+            llm,
+        } => {
+            println!("bootstrapping {year} day {day}!");
+            let client = aoc_client(&config)?;
+            let input_path = input::path(&config.input_dir, year, *day);
+            let puzzle_path = puzzle::path(&config.puzzle_dir, year, *day);
+            for (path, content) in [
+                (&input_path, client.download_input(year, *day)),
+                (&puzzle_path, client.download_puzzle(year, *day)),
+            ] {
+                write_file(path, &content?)?;
+                println!("wrote {}", path.display());
+            }
+            let template_dir = template_dir.as_ref().unwrap_or(&config.template_dir);
+            let templates = Templates::load(template_dir).map_err(|err| {
+                Error::Template(format!(
+                    "couldn't load templates from {}: {err}",
+                    template_dir.display()
+                ))
+            })?;
+            let day_path = scaffold::day_path(year, *day);
+            if day_path.exists() {
+                println!("{} already exists, leaving it alone", day_path.display());
+            } else {
+                let puzzle = Puzzle::load(&config.puzzle_dir, year, *day)
+                    .map_err(Error::io(&puzzle_path))?;
+                let title = puzzle.title.as_deref().unwrap_or("Untitled");
+
+                println!("generating day {day} tests!");
+                let test_case = sample_tests::generate_sample_test_case(
+                    llm.backend(&config)?.as_ref(),
+                    &llm.few_shot_dir,
+                    &puzzle_path,
+                    llm.attempts,
+                )?;
+                let rendered = templates.render_day(year, *day, title, &test_case)?;
+
+                let mod_path = scaffold::year_dir(year).join("mod.rs");
+                if !mod_path.exists() {
+                    write_file(&mod_path, &templates.render_year_mod(year)?)?;
+                    update_source(Path::new("src/main.rs"), |main_rs| {
+                        scaffold::declare_year(main_rs, year)
+                    })?;
+                    update_source(Path::new("src/solution.rs"), |solution_rs| {
+                        scaffold::register_year(solution_rs, year)
+                    })?;
+                }
+                write_file(&day_path, &rendered)?;
+                println!("wrote {}", day_path.display());
+                update_source(&mod_path, |mod_rs| scaffold::register_day(mod_rs, *day))?;
+            }
+            cargo_fmt();
         }
         Commands::PrintSolution { day } => {
-            let (part_1, part_2) = run_day(*day);
+            let solution = find_solution(&registry, year, *day)?;
+            let run = runner::run_timed(solution, &input_source)?;
+            println!("{} - day {day}: {}", run.year, run.title);
 
-            println!("answer_part_1: {part_1:?}");
-            println!("answer_part_2: {part_2:?}");
+            println!(
+                "answer_part_1: {} - {}",
+                run.part_one.0,
+                runner::format_duration(run.part_one.1)
+            );
+            println!(
+                "answer_part_2: {} - {}",
+                run.part_two.0,
+                runner::format_duration(run.part_two.1)
+            );
         }
-        Commands::Submit { day, part } => {
-            let (part_1, part_2) = run_day(*day);
+        Commands::Submit {
+            day,
+            part,
+            wait,
+            force,
+        } => {
+            let solution = find_solution(&registry, year, *day)?;
+            let input = input::load(year, *day, &input_source).map_err(Error::missing_input(
+                year,
+                *day,
+                &input_source,
+            ))?;
 
-            let answer = if part == &1 { part_1 } else { part_2 };
+            let answer = if part == &1 {
+                solution.part_one(&input)
+            } else {
+                solution.part_two(&input)
+            }
+            .map_err(Error::parse(year, *day, &input_source))?;
+            if !answer.is_solved() {
+                return Err(Error::Unsolved {
+                    year,
+                    day: *day,
+                    part: *part,
+                });
+            }
+            let answer_text = answer.to_string();
+
+            let mut log = load_submission_log(&config, year)?;
+            if let Some(refusal) = log.check(*day, *part, &answer_text) {
+                if *force {
+                    eprintln!("warning: {refusal}, submitting anyway");
+                } else {
+                    return Err(Error::Refused(format!(
+                        "not submitting {answer_text}: {refusal}\npass --force to submit it anyway"
+                    )));
+                }
+            }
+            if let Some(remaining) = log.cooldown(*day, chrono::Utc::now().timestamp()) {
+                if *wait {
+                    println!("waiting {}s for the cooldown to end", remaining.as_secs());
+                    std::thread::sleep(remaining);
+                } else {
+                    return Err(Error::Refused(format!(
+                        "cooldown active for another {}s, pass --wait to sleep through it",
+                        remaining.as_secs()
+                    )));
+                }
+            }
             println!("submitting answer for part {part}: {answer}");
 
-            let submission_result = Command::new("aoc")
-                .args([
-                    "--day",
-                    &day.to_string(),
-                    "submit",
-                    &part.to_string(),
-                    &answer.to_string(),
-                ])
-                .output()
-                .expect("failed to submit results");
-            println!("{submission_result:?}");
+            let result = aoc_client(&config)?.submit(year, *day, *part, &answer_text)?;
+            println!("{:?}: {}", result.verdict, result.message);
+            if let Some(wait) = result.wait {
+                println!("wait {}s before submitting again", wait.as_secs());
+            }
+
+            log.record(Attempt::new(
+                *day,
+                *part,
+                &answer_text,
+                &result,
+                chrono::Utc::now().timestamp(),
+            ));
+            if let Err(err) = log.save(&config.answer_dir, year) {
+                eprintln!("couldn't save submission log: {err}");
+            }
+
+            if result.verdict == Verdict::Correct {
+                record_answer(&config, year, *day, *part, &answer.to_string())?;
+            }
         }
-        Commands::TestCompletion { day } => {
-            // using async for just this bit
-            tokio::runtime::Builder::new_current_thread()
-                .enable_io()
-                .enable_time()
-                .build()
-                .unwrap()
-                .block_on(async {
-                    let test_case = generate_sample_test_case(*day).await;
-                    println!("got test case:");
-                    println!("{}", test_case.rust_code);
-                })
+        Commands::TestCompletion { day, llm } => {
+            let test_case = sample_tests::generate_sample_test_case(
+                llm.backend(&config)?.as_ref(),
+                &llm.few_shot_dir,
+                &puzzle::path(&config.puzzle_dir, year, *day),
+                llm.attempts,
+            )?;
+            println!("got test case:");
+            println!("{test_case}");
+        }
+        Commands::RefreshTests { day, llm } => {
+            let day_path = scaffold::day_path(year, *day);
+            let source = std::fs::read_to_string(&day_path).map_err(|err| {
+                Error::io(&day_path)(std::io::Error::new(
+                    err.kind(),
+                    format!("{err}; run `bootstrap {day}` first"),
+                ))
+            })?;
+            let puzzle_path = puzzle::path(&config.puzzle_dir, year, *day);
+            let markdown = aoc_client(&config)?.download_puzzle(year, *day)?;
+            write_file(&puzzle_path, &markdown)?;
+            println!("wrote {}", puzzle_path.display());
+            if Puzzle::parse(&markdown).part_two.is_none() {
+                println!("part two isn't unlocked yet; solve part one first");
+                return Ok(());
+            }
+
+            let samples = sample_tests::generate_samples(
+                llm.backend(&config)?.as_ref(),
+                &llm.few_shot_dir,
+                &puzzle_path,
+                llm.attempts,
+            )?;
+            let merged = sample_tests::merge_part_two(&source, &samples).map_err(|err| {
+                Error::Template(format!(
+                    "couldn't add tests to {}: {err}",
+                    day_path.display()
+                ))
+            })?;
+            if merged.added.is_empty() {
+                println!("no new part two examples for day {day}");
+                return Ok(());
+            }
+            write_file(&day_path, &merged.source)?;
+            for test in &merged.added {
+                println!("added {test} to {}", day_path.display());
+            }
+            cargo_fmt();
+        }
+        Commands::RunAll { skip } => {
+            let mut runs = vec![];
+            for solution in registry.for_year(year).filter(|s| !skip.contains(&s.day())) {
+                match runner::run_timed(solution, &InputSource::Dir(config.input_dir.clone())) {
+                    Ok(run) => runs.push(run),
+                    Err(err) => eprintln!("skipping day {}: {err}", solution.day()),
+                }
+            }
+            print!("{}", runner::summary_table(&runs));
+        }
+        Commands::Bench {
+            day,
+            warmup,
+            runs,
+            save_baseline,
+            baseline,
+            threshold,
+        } => {
+            let solution = find_solution(&registry, year, *day)?;
+            let input = input::load(year, *day, &input_source).map_err(Error::missing_input(
+                year,
+                *day,
+                &input_source,
+            ))?;
+            println!("benchmarking {year} day {day}: {}", solution.title());
+            // fail on bad input up front rather than timing how quickly it's rejected
+            solution
+                .part_one(&input)
+                .and_then(|_| solution.part_two(&input))
+                .map_err(Error::parse(year, *day, &input_source))?;
+
+            let results = bench::Baseline {
+                year,
+                day: *day,
+                part_one: bench::measure(*warmup, *runs, || solution.part_one(&input)),
+                part_two: bench::measure(*warmup, *runs, || solution.part_two(&input)),
+            };
+            println!("part 1: {}", results.part_one);
+            println!("part 2: {}", results.part_two);
+
+            let mut regressed = false;
+            if let Some(path) = baseline {
+                let previous = bench::Baseline::load(path).map_err(Error::io(path))?;
+                if (previous.year, previous.day) != (year, *day) {
+                    eprintln!(
+                        "warning: baseline is for {} day {}, not {year} day {day}",
+                        previous.year, previous.day
+                    );
+                }
+                for (part, old, new) in [
+                    (1, &previous.part_one, &results.part_one),
+                    (2, &previous.part_two, &results.part_two),
+                ] {
+                    let (change, verdict) = bench::compare(old, new, *threshold);
+                    println!("part {part}: {change:+.1}% vs baseline ({verdict:?})");
+                    regressed |= verdict == bench::Verdict::Regression;
+                }
+            }
+
+            if let Some(path) = save_baseline {
+                results.save(path).map_err(Error::io(path))?;
+                println!("saved baseline to {}", path.display());
+            }
+            if regressed {
+                return Err(Error::Regression {
+                    threshold: *threshold,
+                });
+            }
+        }
+        Commands::Verify { skip } => {
+            let store = load_answer_store(&config, year)?;
+            let mut mismatches = 0;
+            let mut failed = 0;
+            for solution in registry.for_year(year).filter(|s| !skip.contains(&s.day())) {
+                let day = solution.day();
+                if !store.has_day(day) {
+                    println!("day {day:02}: no recorded answers");
+                    continue;
+                }
+                let run = match runner::run_timed(
+                    solution,
+                    &InputSource::Dir(config.input_dir.clone()),
+                ) {
+                    Ok(run) => run,
+                    Err(err) => {
+                        failed += 1;
+                        println!("day {day:02}: FAILED {err}");
+                        continue;
+                    }
+                };
+                for (part, answer) in [(1, &run.part_one.0), (2, &run.part_two.0)] {
+                    match store.check(day, part, answer) {
+                        Check::Match => println!("day {day:02} part {part}: ok"),
+                        Check::Mismatch { expected } => {
+                            mismatches += 1;
+                            println!(
+                                "day {day:02} part {part}: MISMATCH expected {expected}, got {answer}"
+                            );
+                        }
+                        Check::Unrecorded => {
+                            println!("day {day:02} part {part}: no recorded answer ({answer})")
+                        }
+                    }
+                }
+            }
+            if mismatches > 0 || failed > 0 {
+                return Err(Error::Mismatch {
+                    count: mismatches,
+                    failed,
+                    store: answer_store::store_path(&config.answer_dir, year),
+                });
+            }
+        }
+        Commands::Puzzle {
+            command: PuzzleCommands::Show { day },
+        } => {
+            let puzzle_path = puzzle::path(&config.puzzle_dir, year, *day);
+            let puzzle = Puzzle::load(&config.puzzle_dir, year, *day).map_err(|err| {
+                Error::io(&puzzle_path)(std::io::Error::new(
+                    err.kind(),
+                    format!("{err}; run `bootstrap {day}` to download it"),
+                ))
+            })?;
+            let store = load_answer_store(&config, year)?;
+            print!(
+                "{}",
+                puzzle.render(
+                    [store.get(*day, 1), store.get(*day, 2)],
+                    std::io::stdout().is_terminal()
+                )
+            );
+        }
+        Commands::List => {
+            for solution in registry.for_year(year) {
+                println!(
+                    "{} day {:02}: {}",
+                    solution.year(),
+                    solution.day(),
+                    solution.title()
+                );
+            }
+            let missing: Vec<String> = registry
+                .missing_days(year)
+                .iter()
+                .map(|d| d.to_string())
+                .collect();
+            if !missing.is_empty() {
+                println!("not implemented: {}", missing.join(", "));
+            }
         }
     }
+    Ok(())
 }
 
-const DAY_FILE: &str = r#"
-pub(crate) fn run() -> (usize, usize) {
-    let input_string = get_input_string();
-    (answer_part_1(input_string), answer_part_2(input_string))
+fn find_solution(registry: &Registry, year: u16, day: usize) -> Result<&dyn Solution, Error> {
+    registry
+        .get(year, day)
+        .ok_or_else(|| Error::NotImplemented {
+            year,
+            day,
+            implemented: registry.for_year(year).map(|s| s.day()).collect(),
+        })
 }
 
-fn answer_part_1(lines: &str) -> usize {
-    lines.len()
+fn aoc_client(config: &Config) -> Result<AocClient, Error> {
+    let session = client::find_session(config.session_file.as_deref())?;
+    Ok(AocClient::new(&config.base_url, &session))
 }
 
-fn answer_part_2(lines: &str) -> usize {
-    lines.len()
+fn load_answer_store(config: &Config, year: u16) -> Result<AnswerStore, Error> {
+    AnswerStore::load(&config.answer_dir, year).map_err(Error::io(answer_store::store_path(
+        &config.answer_dir,
+        year,
+    )))
 }
 
-fn get_input_string() -> &'static str {
-    include_str!("../inputs/day{{formatted_day}}.txt")
+fn load_submission_log(config: &Config, year: u16) -> Result<SubmissionLog, Error> {
+    SubmissionLog::load(&config.answer_dir, year)
+        .map_err(Error::io(submissions::log_path(&config.answer_dir, year)))
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::day{{formatted_day}}::{answer_part_1, answer_part_2, get_input_string};
-    #[test]
-    fn test_all_lines() {
-        let lines = get_input_string();
-
-        assert_eq!(answer_part_1(lines), 0);
-        assert_eq!(answer_part_2(lines), 0);
-    }
-
-    {{{test_case}}}
+fn record_answer(
+    config: &Config,
+    year: u16,
+    day: usize,
+    part: usize,
+    answer: &str,
+) -> Result<(), Error> {
+    let mut store = load_answer_store(config, year)?;
+    store.record(day, part, answer);
+    store
+        .save(&config.answer_dir, year)
+        .map_err(Error::io(answer_store::store_path(
+            &config.answer_dir,
+            year,
+        )))?;
+    println!("recorded part {part} answer for day {day}");
+    Ok(())
 }
-"#;
-
-const DAY_3_TEST_EXAMPLE: &str = r##"
-    const SAMPLE_INPUT: &'static str = r#"467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598.."#;
-
-    #[test]
-    fn test_short_example() {
-        assert_eq!(answer_part_1(SAMPLE_INPUT), 4361);
-        assert_eq!(answer_part_2(SAMPLE_INPUT), 467835);
+
+/// Write a file, creating its directory first if needed.
+fn write_file(path: &Path, content: &str) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(Error::io(parent))?;
     }
-"##;
-
-async fn generate_sample_test_case(day: usize) -> RustCodeResponse {
-    let client = Client::new();
-    let puzzle_input_str = format!("puzzles/day{day:02}.md");
-    let puzzle_input_path = Path::new(&puzzle_input_str);
-    let day_3_puzzle = include_str!("../puzzles/day03.md");
-    let mut current_puzzle = String::new();
-    File::open(puzzle_input_path)
-        .unwrap_or_else(|_| panic!("{puzzle_input_str} not found"))
-        .read_to_string(&mut current_puzzle)
-        .expect("couldn't read file to string");
-
-    let request = CreateChatCompletionRequestArgs::default()
-        .max_tokens(1024u16)
-        .model("gpt-4-1106-preview")
-        .response_format(ChatCompletionResponseFormat { r#type: ChatCompletionResponseFormatType::JsonObject })
-        .messages([
-            ChatCompletionRequestSystemMessageArgs::default()
-                .content("You are a puzzle sample test creation assistant. You take puzzle inputs and return a valid test case in Rust. Do not use html encoded characters in your response. Do not wrap the response in a code block! Write the code as it would be inserted in a Rust source file. Return only sample rust test cases and rust constants, do not try to implement the puzzle solution! Only output valid JSON! Rust code should be placed in the JSON object under the `rust_code` key only!")
-                .build().unwrap()
-                .into(),
-            ChatCompletionRequestSystemMessageArgs::default()
-                .content(day_3_puzzle)
-                .name("example_user")
-                .build().unwrap()
-                .into(),
-            ChatCompletionRequestSystemMessageArgs::default()
-                .content(DAY_3_TEST_EXAMPLE)
-                .name("example_assistant")
-                .build().unwrap()
-                .into(),
-            ChatCompletionRequestUserMessageArgs::default()
-                .content(current_puzzle)
-                .build().unwrap()
-                .into(),
-        ])
-        .build().unwrap();
-
-    let content = client
-        .chat()
-        .create(request)
-        .await
-        .expect("should have gotten a successful response")
-        .choices
-        .first()
-        .expect("didn't return any chat responses")
-        .message
-        .content
-        .clone()
-        .expect("content was unexpectedly empty");
-
-    serde_json::from_str(&content).unwrap()
+    std::fs::write(path, content).map_err(Error::io(path))
 }
 
-#[derive(serde::Deserialize)]
-struct RustCodeResponse {
-    rust_code: String,
+/// Rewrite a source file in place and report it.
+fn update_source(path: &Path, update: impl FnOnce(&str) -> String) -> Result<(), Error> {
+    let content = std::fs::read_to_string(path).map_err(Error::io(path))?;
+    write_file(path, &update(&content))?;
+    println!("updated {}", path.display());
+    Ok(())
 }
 
-fn run_day(day: usize) -> (usize, usize) {
-    match day {
-        1 => aoc2023::day01::run(),
-        2 => aoc2023::day02::run(),
-        3 => aoc2023::day03::run(),
-        4 => aoc2023::day04::run(),
-        5 => aoc2023::day05::run(),
-        6 => aoc2023::day06::run(),
-        7 => aoc2023::day07::run(),
-        8 => aoc2023::day08::run(),
-        9 => aoc2023::day09::run(),
-        10 => aoc2023::day10::run(),
-        11 => aoc2023::day11::run(),
-        12 => aoc2023::day12::run(),
-        13 => aoc2023::day13::run(),
-        14 => aoc2023::day14::run(),
-        15 => aoc2023::day15::run(),
-        16 => aoc2023::day16::run(),
-        _ => panic!("no such day"),
+/// Tidy up generated code. Failing to is only worth a warning since the files are written.
+fn cargo_fmt() {
+    match Command::new("cargo").arg("fmt").status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("warning: cargo fmt exited with {status}"),
+        Err(err) => eprintln!("warning: couldn't run cargo fmt: {err}"),
     }
 }
 
+mod answer;
+mod answer_store;
 mod aoc2023;
+mod bench;
+mod client;
+mod config;
+mod cycle;
+mod error;
+mod geometry;
+mod grid;
+mod input;
+mod interval;
+mod llm;
+mod parse;
+mod puzzle;
+mod runner;
+mod sample_tests;
+mod scaffold;
+mod solution;
+mod submissions;
@@ -0,0 +1,176 @@
+use std::str::FromStr;
+
+/// Where and why a puzzle input couldn't be parsed. Lines and columns count from 1 and are
+/// relative to the text the failing parser was given; callers that handed it a piece of a
+/// larger input move the error into place with [`ParseError::within`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParseError {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) message: String,
+}
+
+impl ParseError {
+    /// An error at the very start of the text, e.g. from parsing a single character.
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self::at(1, 1, message)
+    }
+
+    pub(crate) fn at(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Relocate an error found in a piece of text that starts at `line` and `column` of the
+    /// enclosing text.
+    pub(crate) fn within(self, line: usize, column: usize) -> Self {
+        Self {
+            column: if self.line == 1 {
+                self.column + column - 1
+            } else {
+                self.column
+            },
+            line: self.line + line - 1,
+            message: self.message,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// Parse every line of `input` with `parse`, placing errors on the line they came from.
+pub(crate) fn lines<T>(
+    input: &str,
+    parse: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse(line).map_err(|e| e.within(index + 1, 1)))
+        .collect()
+}
+
+/// Parse every character of a line, reporting the column of the first invalid one.
+pub(crate) fn chars<T: TryFrom<char, Error = ParseError>>(
+    line: &str,
+) -> Result<Vec<T>, ParseError> {
+    line.chars()
+        .enumerate()
+        .map(|(index, c)| T::try_from(c).map_err(|e| e.within(1, index + 1)))
+        .collect()
+}
+
+/// A rectangular grid of characters, one row per line. A ragged or empty grid is an error,
+/// which usually means the input was cut short.
+pub(crate) fn rows<T: TryFrom<char, Error = ParseError>>(
+    input: &str,
+) -> Result<Vec<Vec<T>>, ParseError> {
+    let rows = lines(input, chars)?;
+    let width = rows
+        .first()
+        .map(Vec::len)
+        .filter(|width| *width > 0)
+        .ok_or_else(|| ParseError::new("expected a grid, found an empty line"))?;
+    if let Some(index) = rows.iter().position(|row| row.len() != width) {
+        return Err(ParseError::at(
+            index + 1,
+            rows[index].len().min(width) + 1,
+            format!(
+                "expected a row of {width} tiles, found {}",
+                rows[index].len()
+            ),
+        ));
+    }
+    Ok(rows)
+}
+
+/// The space-separated fields of a line, each with the column it starts at.
+pub(crate) fn fields(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut column = 1;
+    line.split(' ').filter_map(move |field| {
+        let start = column;
+        column += field.chars().count() + 1;
+        (!field.is_empty()).then_some((start, field))
+    })
+}
+
+/// A number, or an error describing what was found instead.
+pub(crate) fn number<T: FromStr>(field: &str) -> Result<T, ParseError> {
+    field
+        .parse()
+        .map_err(|_| ParseError::new(format!("expected a number, found `{field}`")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::{fields, lines, number, rows, ParseError};
+
+    #[derive(Debug, PartialEq)]
+    struct Bit(bool);
+
+    impl TryFrom<char> for Bit {
+        type Error = ParseError;
+
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                '0' => Ok(Bit(false)),
+                '1' => Ok(Bit(true)),
+                _ => Err(ParseError::new(format!("unknown bit `{c}`"))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_rows() {
+        assert_eq!(
+            rows::<Bit>("01\n10").unwrap(),
+            [[Bit(false), Bit(true)], [Bit(true), Bit(false)]]
+        );
+        assert_eq!(
+            rows::<Bit>("01\n1x").unwrap_err(),
+            ParseError::at(2, 2, "unknown bit `x`")
+        );
+        assert_eq!(
+            rows::<Bit>("011\n10").unwrap_err().to_string(),
+            "line 2, column 3: expected a row of 3 tiles, found 2"
+        );
+        assert!(rows::<Bit>("").is_err());
+    }
+
+    #[test]
+    fn test_fields_and_numbers() {
+        assert_eq!(
+            fields(" 12  3 x").collect::<Vec<_>>(),
+            [(2, "12"), (6, "3"), (8, "x")]
+        );
+        assert_eq!(
+            lines("1 2\n3 x", |line| fields(line)
+                .map(|(column, field)| number::<u8>(field).map_err(|e| e.within(1, column)))
+                .collect::<Result<Vec<_>, _>>())
+            .unwrap_err(),
+            ParseError::at(2, 3, "expected a number, found `x`")
+        );
+    }
+
+    #[test]
+    fn test_within() {
+        let err = ParseError::at(1, 4, "oops");
+        assert_eq!(err.clone().within(3, 10), ParseError::at(3, 13, "oops"));
+        // only errors on the first line of the piece share its starting column
+        assert_eq!(
+            ParseError::at(2, 4, "oops").within(3, 10),
+            ParseError::at(4, 4, "oops")
+        );
+    }
+}
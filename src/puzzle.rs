@@ -0,0 +1,472 @@
+use crate::sample_tests::{Expected, NamedSample};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+lazy_static! {
+    /// An answer the puzzle text highlights, written by html2md as `` `*42*` ``.
+    static ref EMPHASISED_CODE_RE: Regex = Regex::new(r"`\*([^`*]+)\*`").unwrap();
+    /// How the puzzles introduce a new example input, e.g. "For example:" or "Here is an
+    /// example engine schematic:".
+    static ref EXAMPLE_INTRO_RE: Regex = Regex::new(r"\b(for|an) example\b").unwrap();
+    static ref TITLE_RE: Regex = Regex::new(r"--- Day \d+: (.*?) ---").unwrap();
+    static ref ACCEPTED_RE: Regex = Regex::new(r"^Your puzzle answer was `([^`]*)`").unwrap();
+    static ref LINK_RE: Regex = Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap();
+    static ref EMPHASIS_RE: Regex = Regex::new(r"`?\*([^*\s][^*]*?)\*`?").unwrap();
+}
+
+const PART_TWO_HEADING: &str = "--- Part Two ---";
+/// Lines that start the site's footer after a part's question.
+const FOOTER_STARTS: [&str; 6] = [
+    "Your puzzle answer was",
+    "Answer:",
+    "The first half of this puzzle is complete",
+    "Both parts of this puzzle are complete",
+    "To begin, [get your puzzle input]",
+    "Although it hasn't changed",
+];
+/// Words in the line introducing a code block that mean it re-quotes an earlier example
+/// rather than giving a new input.
+const REFERS_BACK: [&str; 4] = ["above", "again", "before", "same"];
+
+/// An example input and the answer the puzzle text gives for it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Sample {
+    pub(crate) input: String,
+    pub(crate) answer: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Samples {
+    pub(crate) part_one: Sample,
+    /// Part two usually reuses part one's input; `input` is only different when the
+    /// part introduces a new example.
+    pub(crate) part_two: Option<Sample>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Block<'a> {
+    /// A line of prose.
+    Text(&'a str),
+    /// A fenced code block and the prose line right before it.
+    Code { intro: &'a str, lines: Vec<&'a str> },
+}
+
+fn blocks(markdown: &str) -> Vec<Block<'_>> {
+    let mut blocks = vec![];
+    let mut lines = markdown.lines();
+    let mut intro = "";
+    while let Some(line) = lines.next() {
+        if line.trim() == "```" {
+            let mut code: Vec<&str> = lines.by_ref().take_while(|l| l.trim() != "```").collect();
+            while code.last().is_some_and(|l| l.trim().is_empty()) {
+                code.pop();
+            }
+            blocks.push(Block::Code { intro, lines: code });
+        } else if !line.trim().is_empty() {
+            intro = line;
+            blocks.push(Block::Text(line));
+        }
+    }
+    blocks
+}
+
+fn last_answer(block: &Block) -> Option<String> {
+    match block {
+        Block::Text(text) => EMPHASISED_CODE_RE
+            .captures_iter(text)
+            .last()
+            .map(|c| c[1].to_owned()),
+        Block::Code { .. } => None,
+    }
+}
+
+/// The part's example input and answer: the first code block introduced as an example that
+/// isn't a re-quote of an earlier one, and the last highlighted answer before the next such
+/// block that follows an answer.
+fn find_sample(part: &[Block], earlier: &[Vec<&str>]) -> Option<(Vec<String>, String)> {
+    let mut seen: Vec<&Vec<&str>> = earlier.iter().collect();
+    let mut is_example = vec![false; part.len()];
+    for (i, block) in part.iter().enumerate() {
+        if let Block::Code { intro, lines } = block {
+            let intro = intro.to_lowercase();
+            let refers_back = REFERS_BACK.iter().any(|word| intro.contains(word));
+            let fragment = seen
+                .iter()
+                .any(|prior| lines.iter().all(|line| prior.contains(line)));
+            is_example[i] =
+                EXAMPLE_INTRO_RE.is_match(&intro) && !lines.is_empty() && !refers_back && !fragment;
+            seen.push(lines);
+        }
+    }
+
+    let start = is_example.iter().position(|example| *example)?;
+    let mut answer = None;
+    for (i, block) in part.iter().enumerate().skip(start + 1) {
+        // the next example's intro often states its own answer
+        let next_is_example = is_example.get(i + 1) == Some(&true);
+        if (is_example[i] || next_is_example) && answer.is_some() {
+            break;
+        }
+        answer = last_answer(block).or(answer);
+    }
+    match &part[start] {
+        Block::Code { lines, .. } => Some((lines.iter().map(|l| l.to_string()).collect(), answer?)),
+        Block::Text(_) => unreachable!(),
+    }
+}
+
+pub(crate) const DEFAULT_PUZZLE_DIR: &str = "puzzles";
+
+pub(crate) fn path(dir: &Path, year: u16, day: usize) -> PathBuf {
+    dir.join(format!("{year}/day{day:02}.md"))
+}
+
+/// A puzzle description as saved by `bootstrap`, split into its parts.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Puzzle {
+    pub(crate) title: Option<String>,
+    pub(crate) part_one: Part,
+    /// Only present once part one has been solved.
+    pub(crate) part_two: Option<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Part {
+    /// The markdown of the part, without its heading and the site's footer.
+    pub(crate) body: String,
+    /// Every fenced code block in the part.
+    pub(crate) examples: Vec<String>,
+    /// Every highlighted answer in the part, in order.
+    pub(crate) emphasised_answers: Vec<String>,
+    /// The answer the site accepted, once the part is solved.
+    pub(crate) accepted: Option<String>,
+}
+
+impl Part {
+    fn parse(lines: &[&str]) -> Self {
+        let accepted = lines
+            .iter()
+            .find_map(|line| ACCEPTED_RE.captures(line).map(|c| c[1].to_owned()));
+        let end = lines
+            .iter()
+            .position(|line| FOOTER_STARTS.iter().any(|start| line.starts_with(start)))
+            .unwrap_or(lines.len());
+        let body = lines[..end]
+            .iter()
+            .filter(|line| !line.contains("--- Day ") && !line.contains(PART_TWO_HEADING))
+            .filter(|line| **line != "----------")
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_owned();
+
+        let mut examples = vec![];
+        let mut emphasised_answers = vec![];
+        for block in blocks(&body) {
+            match block {
+                Block::Code { lines, .. } => examples.push(lines.join("\n")),
+                Block::Text(text) => emphasised_answers.extend(
+                    EMPHASISED_CODE_RE
+                        .captures_iter(text)
+                        .map(|c| c[1].to_owned()),
+                ),
+            }
+        }
+        Self {
+            body,
+            examples,
+            emphasised_answers,
+            accepted,
+        }
+    }
+
+    /// The body as terminal text: links reduced to their text, escapes removed, code blocks
+    /// indented and emphasis in bold when `bold` is set.
+    fn render(&self, bold: bool) -> String {
+        let mut output = String::new();
+        let mut in_code = false;
+        for line in self.body.lines() {
+            if line.trim() == "```" {
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                output.push_str("    ");
+                output.push_str(line);
+            } else {
+                let text = LINK_RE.replace_all(line, "$1").replace("\\", "");
+                if bold {
+                    output.push_str(&EMPHASIS_RE.replace_all(&text, "\x1b[1m$1\x1b[0m"));
+                } else {
+                    output.push_str(&text);
+                }
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+impl Puzzle {
+    pub(crate) fn parse(markdown: &str) -> Self {
+        let lines: Vec<&str> = markdown.lines().collect();
+        let split = lines
+            .iter()
+            .position(|line| line.contains(PART_TWO_HEADING))
+            .unwrap_or(lines.len());
+        Self {
+            title: TITLE_RE.captures(markdown).map(|c| c[1].trim().to_owned()),
+            part_one: Part::parse(&lines[..split]),
+            part_two: (split < lines.len()).then(|| Part::parse(&lines[split..])),
+        }
+    }
+
+    pub(crate) fn load(dir: &Path, year: u16, day: usize) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path(dir, year, day))?))
+    }
+
+    /// The whole puzzle as terminal text, with each part followed by its accepted answer
+    /// and the answer recorded for it in `answers/`.
+    pub(crate) fn render(&self, recorded: [Option<&str>; 2], bold: bool) -> String {
+        let mut output = format!(
+            "--- {} ---\n\n",
+            self.title.as_deref().unwrap_or("Untitled")
+        );
+        let parts = [Some(&self.part_one), self.part_two.as_ref()];
+        for (number, (part, recorded)) in parts.into_iter().zip(recorded).enumerate() {
+            let Some(part) = part else {
+                continue;
+            };
+            if number == 1 {
+                output.push_str(&format!("\n{PART_TWO_HEADING}\n\n"));
+            }
+            output.push_str(&part.render(bold));
+            output.push_str(&format!(
+                "\naccepted answer: {}\n",
+                part.accepted.as_deref().unwrap_or("none")
+            ));
+            output.push_str(&format!(
+                "recorded answer: {}\n",
+                recorded.unwrap_or("none")
+            ));
+        }
+        output
+    }
+}
+
+/// Pull the example inputs and expected answers out of a puzzle's markdown, as written by
+/// `bootstrap`. Returns `None` when part one has no recognisable example.
+pub(crate) fn extract_samples(markdown: &str) -> Option<Samples> {
+    let blocks = blocks(markdown);
+    let split = blocks
+        .iter()
+        .position(|block| matches!(block, Block::Text(text) if text.contains(PART_TWO_HEADING)))
+        .unwrap_or(blocks.len());
+    let (part_one, part_two) = blocks.split_at(split);
+
+    let (input, answer) = find_sample(part_one, &[])?;
+    let part_one_lines: Vec<&str> = input.iter().map(|l| l.as_str()).collect();
+    let part_two = if part_two.is_empty() {
+        None
+    } else {
+        match find_sample(part_two, &[part_one_lines]) {
+            Some((lines, answer)) => Some(Sample {
+                input: lines.join("\n"),
+                answer,
+            }),
+            None => part_two
+                .iter()
+                .rev()
+                .find_map(last_answer)
+                .map(|answer| Sample {
+                    input: input.join("\n"),
+                    answer,
+                }),
+        }
+    };
+
+    Some(Samples {
+        part_one: Sample {
+            input: input.join("\n"),
+            answer,
+        },
+        part_two,
+    })
+}
+
+impl Samples {
+    /// One named sample per distinct input, for [`crate::sample_tests::render`].
+    pub(crate) fn named(&self) -> Vec<NamedSample> {
+        let answer = |sample: &Sample| Some(Expected::Text(sample.answer.clone()));
+        match &self.part_two {
+            Some(part_two) if part_two.input != self.part_one.input => vec![
+                NamedSample {
+                    name: "part_1_example".to_owned(),
+                    input: self.part_one.input.clone(),
+                    part_1: answer(&self.part_one),
+                    part_2: None,
+                },
+                NamedSample {
+                    name: "part_2_example".to_owned(),
+                    input: part_two.input.clone(),
+                    part_1: None,
+                    part_2: answer(part_two),
+                },
+            ],
+            part_two => vec![NamedSample {
+                name: "example".to_owned(),
+                input: self.part_one.input.clone(),
+                part_1: answer(&self.part_one),
+                part_2: part_two.as_ref().and_then(answer),
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        puzzle::{extract_samples, Puzzle, Sample},
+        sample_tests::{render, validate},
+        solution::Registry,
+    };
+
+    fn puzzle(day: usize) -> String {
+        std::fs::read_to_string(format!("puzzles/2023/day{day:02}.md")).unwrap()
+    }
+
+    #[test]
+    fn test_extract_day_09() {
+        let samples = extract_samples(&puzzle(9)).unwrap();
+        let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45";
+
+        assert_eq!(
+            samples.part_one,
+            Sample {
+                input: input.to_owned(),
+                answer: "114".to_owned()
+            }
+        );
+        assert_eq!(
+            samples.part_two,
+            Some(Sample {
+                input: input.to_owned(),
+                answer: "2".to_owned()
+            })
+        );
+        assert_eq!(validate(&render(&samples.named())), Ok(()));
+    }
+
+    #[test]
+    fn test_new_part_two_example() {
+        let samples = extract_samples(&puzzle(1)).unwrap();
+        let part_two = samples.part_two.as_ref().unwrap();
+
+        assert_eq!(samples.part_one.answer, "142");
+        assert!(part_two.input.starts_with("two1nine"));
+        assert_eq!(part_two.answer, "281");
+        assert!(render(&samples.named())
+            .contains("assert_eq!(answer_part_2(SAMPLE_PART_2_EXAMPLE), 281);"));
+    }
+
+    #[test]
+    fn test_skips_requoted_fragments() {
+        // part one quotes the seed-to-soil map again after the full example
+        let samples = extract_samples(&puzzle(5)).unwrap();
+        assert!(samples.part_one.input.starts_with("seeds: 79 14 55 13"));
+        assert_eq!(samples.part_one.answer, "35");
+    }
+
+    /// The extracted answers should agree with the solutions. Day 10 introduces its first
+    /// example without a starting tile and day 11 part 2 uses a different expansion factor in
+    /// its example.
+    #[test]
+    fn test_samples_match_solutions() {
+        let registry = Registry::new();
+        for day in [1, 2, 3, 4, 5, 6, 7, 8, 9, 11, 12, 13, 14, 15] {
+            let samples = extract_samples(&puzzle(day)).unwrap();
+            let solution = registry.get(2023, day).unwrap();
+            assert_eq!(
+                solution
+                    .part_one(&samples.part_one.input)
+                    .unwrap()
+                    .to_string(),
+                samples.part_one.answer,
+                "day {day} part 1"
+            );
+            if day == 11 {
+                continue;
+            }
+            let part_two = samples.part_two.unwrap();
+            assert_eq!(
+                solution.part_two(&part_two.input).unwrap().to_string(),
+                part_two.answer,
+                "day {day} part 2"
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_example() {
+        assert_eq!(
+            extract_samples("\\--- Day 1: Nothing ---\n\nNo code here."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_puzzle() {
+        let puzzle = Puzzle::parse(&puzzle(9));
+        assert_eq!(puzzle.title.as_deref(), Some("Mirage Maintenance"));
+
+        let part_one = &puzzle.part_one;
+        assert!(part_one.body.starts_with("You ride the camel"));
+        assert!(part_one
+            .body
+            .ends_with("*What is the sum of these extrapolated values?*"));
+        assert_eq!(part_one.examples.len(), 8);
+        assert_eq!(
+            part_one.examples[0],
+            "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45"
+        );
+        assert_eq!(
+            part_one.emphasised_answers,
+            ["3", "18", "18", "28", "68", "114"]
+        );
+        assert_eq!(part_one.accepted.as_deref(), Some("1853145119"));
+
+        let part_two = puzzle.part_two.unwrap();
+        assert!(part_two.body.starts_with("Of course"));
+        assert_eq!(part_two.emphasised_answers.last().unwrap(), "2");
+        assert_eq!(part_two.accepted, None);
+    }
+
+    #[test]
+    fn test_unsolved_puzzle() {
+        let puzzle = Puzzle::parse(&puzzle(16));
+        assert_eq!(puzzle.title.as_deref(), Some("The Floor Will Be Lava"));
+        assert_eq!(puzzle.part_one.accepted, None);
+        assert!(puzzle
+            .part_one
+            .body
+            .ends_with("*how many tiles end up being energized?*"));
+        assert_eq!(puzzle.part_two, None);
+        assert_eq!(Puzzle::parse("no heading").title, None);
+    }
+
+    #[test]
+    fn test_render_puzzle() {
+        let rendered = Puzzle::parse(&puzzle(9)).render([Some("1853145119"), Some("923")], false);
+
+        assert!(rendered.starts_with("--- Mirage Maintenance ---\n\nYou ride the camel"));
+        assert!(rendered.contains("There's even a hang glider partially buried"));
+        assert!(rendered.contains("\n    0 3 6 9 12 15\n"));
+        assert!(rendered.contains("\n--- Part Two ---\n"));
+        assert!(rendered.ends_with("accepted answer: none\nrecorded answer: 923\n"));
+
+        let bold = Puzzle::parse(&puzzle(9)).render([None, None], true);
+        assert!(bold.contains("you get \x1b[1m114\x1b[0m."));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    answer::Answer,
+    error::Error,
+    input::{self, InputSource},
+    solution::Solution,
+};
+use std::time::{Duration, Instant};
+
+/// Answers and timings from a single run of one day.
+pub(crate) struct DayRun {
+    pub(crate) year: u16,
+    pub(crate) day: usize,
+    pub(crate) title: &'static str,
+    pub(crate) load_time: Duration,
+    pub(crate) part_one: (Answer, Duration),
+    pub(crate) part_two: (Answer, Duration),
+}
+
+impl DayRun {
+    pub(crate) fn total_time(&self) -> Duration {
+        self.load_time + self.part_one.1 + self.part_two.1
+    }
+}
+
+/// Load the input for `solution` and run both parts, timing each step separately. Solutions
+/// parse the input as part of each part, so that's included in the part times rather than
+/// the load time.
+pub(crate) fn run_timed(solution: &dyn Solution, source: &InputSource) -> Result<DayRun, Error> {
+    let (year, day) = (solution.year(), solution.day());
+    let start = Instant::now();
+    let input = input::load(year, day, source).map_err(Error::missing_input(year, day, source))?;
+    let load_time = start.elapsed();
+
+    let (part_one, part_one_time) = time(|| solution.part_one(&input));
+    let (part_two, part_two_time) = time(|| solution.part_two(&input));
+    Ok(DayRun {
+        year,
+        day,
+        title: solution.title(),
+        load_time,
+        part_one: (
+            part_one.map_err(Error::parse(year, day, source))?,
+            part_one_time,
+        ),
+        part_two: (
+            part_two.map_err(Error::parse(year, day, source))?,
+            part_two_time,
+        ),
+    })
+}
+
+pub(crate) fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Microseconds below a millisecond, then milliseconds, then seconds.
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1_000 {
+        format!("{micros}μs")
+    } else if micros < 1_000_000 {
+        format!("{:.2}ms", micros as f64 / 1_000.0)
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+/// Render runs as a table with one row per day and a totals row.
+pub(crate) fn summary_table(runs: &[DayRun]) -> String {
+    let answer_width = runs
+        .iter()
+        .flat_map(|r| {
+            [
+                r.part_one.0.to_string().len(),
+                r.part_two.0.to_string().len(),
+            ]
+        })
+        .chain(std::iter::once("part 1".len()))
+        .max()
+        .unwrap_or_default();
+    let title_width = runs
+        .iter()
+        .map(|r| r.title.len())
+        .chain(std::iter::once("title".len()))
+        .max()
+        .unwrap_or_default();
+
+    let row = |cells: [&str; 8]| {
+        format!(
+            "{:>3}  {:<title_width$}  {:>answer_width$}  {:>answer_width$}  {:>9}  {:>9}  {:>9}  {:>9}\n",
+            cells[0], cells[1], cells[2], cells[3], cells[4], cells[5], cells[6], cells[7]
+        )
+    };
+
+    let mut output = row([
+        "day", "title", "part 1", "part 2", "load", "time 1", "time 2", "total",
+    ]);
+    for run in runs {
+        output.push_str(&row([
+            &run.day.to_string(),
+            run.title,
+            &run.part_one.0.to_string(),
+            &run.part_two.0.to_string(),
+            &format_duration(run.load_time),
+            &format_duration(run.part_one.1),
+            &format_duration(run.part_two.1),
+            &format_duration(run.total_time()),
+        ]));
+    }
+
+    let load: Duration = runs.iter().map(|r| r.load_time).sum();
+    let part_one: Duration = runs.iter().map(|r| r.part_one.1).sum();
+    let part_two: Duration = runs.iter().map(|r| r.part_two.1).sum();
+    output.push_str(&row([
+        "",
+        "total",
+        "",
+        "",
+        &format_duration(load),
+        &format_duration(part_one),
+        &format_duration(part_two),
+        &format_duration(load + part_one + part_two),
+    ]));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answer::Answer,
+        runner::{format_duration, summary_table, DayRun},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(1_500)), "1μs");
+        assert_eq!(format_duration(Duration::from_micros(12_345)), "12.35ms");
+        assert_eq!(format_duration(Duration::from_millis(2_500)), "2.50s");
+    }
+
+    #[test]
+    fn test_summary_table_totals() {
+        let runs = vec![
+            DayRun {
+                year: 2023,
+                day: 1,
+                title: "Trebuchet?!",
+                load_time: Duration::from_micros(10),
+                part_one: (Answer::Integer(142), Duration::from_micros(100)),
+                part_two: (Answer::Integer(281), Duration::from_micros(200)),
+            },
+            DayRun {
+                year: 2023,
+                day: 9,
+                title: "Mirage Maintenance",
+                load_time: Duration::from_micros(20),
+                part_one: (Answer::Signed(114), Duration::from_micros(300)),
+                part_two: (Answer::Signed(-2), Duration::from_micros(400)),
+            },
+        ];
+        let table = summary_table(&runs);
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].contains("Mirage Maintenance"));
+        assert!(lines[2].contains("-2"));
+        assert!(lines[3].contains("total"));
+        assert!(lines[3].ends_with("1.03ms"));
+        assert_eq!(runs[1].total_time(), Duration::from_micros(720));
+    }
+}
@@ -0,0 +1,803 @@
+use crate::{
+    llm::{LlmBackend, LlmError, Message},
+    puzzle::extract_samples,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+use syn::{
+    parse::Parser, punctuated::Punctuated, spanned::Spanned, visit::Visit, Expr, ExprCall,
+    ExprPath, Item, Macro, PatIdent, Token,
+};
+
+pub(crate) const DEFAULT_ATTEMPTS: usize = 3;
+pub(crate) const DEFAULT_FEW_SHOT_DIR: &str = "few_shot";
+
+const SYSTEM_PROMPT: &str = "You are a puzzle sample test creation assistant. You read Advent of Code puzzle descriptions and list the example inputs they contain together with the answers the text gives for them. Do not try to solve the puzzle, only report examples and answers stated in the text! Do not use html encoded characters. Only output valid JSON! Reply with a JSON object with a `samples` array. Each sample has a short snake_case `name`, the exact example `input` text, and `part_1` and/or `part_2` holding the expected answer for the part(s) that example checks. Leave out a part the example doesn't give an answer for.";
+
+/// Functions generated tests may call; everything else has to be a constant they define.
+const ALLOWED_FUNCTIONS: [&str; 2] = ["answer_part_1", "answer_part_2"];
+const ALLOWED_MACROS: [&str; 3] = ["assert", "assert_eq", "assert_ne"];
+
+/// An expected answer, as a JSON number or string.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Expected {
+    Integer(i64),
+    Text(String),
+}
+
+impl Expected {
+    /// Numbers are written as integer literals and anything else as a string literal.
+    fn literal(&self) -> String {
+        match self {
+            Expected::Integer(n) => n.to_string(),
+            Expected::Text(text) if text.parse::<i128>().is_ok() => text.clone(),
+            Expected::Text(text) => format!("{text:?}"),
+        }
+    }
+}
+
+/// One example input and the answers it checks, which becomes its own test.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct NamedSample {
+    pub(crate) name: String,
+    pub(crate) input: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) part_1: Option<Expected>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) part_2: Option<Expected>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SamplesResponse {
+    samples: Vec<NamedSample>,
+}
+
+/// A worked example for the prompt: a puzzle and the samples that should be found in it.
+/// Each lives in the few-shot directory as a JSON file naming the puzzle's markdown file:
+///
+/// ```json
+/// {"puzzle": "puzzles/2023/day08.md", "samples": [{"name": "...", "input": "...", "part_1": 2}]}
+/// ```
+#[derive(serde::Deserialize)]
+struct FewShotFile {
+    puzzle: std::path::PathBuf,
+    samples: Vec<NamedSample>,
+}
+
+pub(crate) struct FewShot {
+    puzzle: String,
+    response: String,
+}
+
+/// Load every few-shot example in `dir`, in file name order. A missing directory means
+/// prompting without examples.
+pub(crate) fn load_few_shots(dir: &Path) -> std::io::Result<Vec<FewShot>> {
+    let mut paths: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+        Err(e) => return Err(e),
+    };
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            let file: FewShotFile =
+                serde_json::from_str(&std::fs::read_to_string(path)?).map_err(|e| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("{}: {e}", path.display()),
+                    )
+                })?;
+            Ok(FewShot {
+                puzzle: std::fs::read_to_string(&file.puzzle)?,
+                response: serde_json::to_string(&SamplesResponse {
+                    samples: file.samples,
+                })?,
+            })
+        })
+        .collect()
+}
+
+/// Render samples as constants and one test per sample, checking the parts it's tagged with.
+pub(crate) fn render(samples: &[NamedSample]) -> String {
+    render_reusing(samples, &HashMap::new())
+}
+
+/// [`render`], using the constants in `existing` for inputs they already hold. Keys are
+/// compared after [`trim_lines`].
+fn render_reusing(samples: &[NamedSample], existing: &HashMap<String, String>) -> String {
+    let mut constants = String::new();
+    let mut tests = String::new();
+    for sample in samples {
+        let name = identifier(&sample.name);
+        let constant = match existing.get(&trim_lines(&sample.input)) {
+            Some(constant) => constant.clone(),
+            None => {
+                let constant = format!("SAMPLE_{}", name.to_uppercase());
+                constants.push_str(&format!(
+                    "const {constant}: &str = {};\n",
+                    raw_string(&sample.input)
+                ));
+                constant
+            }
+        };
+        tests.push_str(&format!("\n#[test]\nfn test_{name}() {{\n"));
+        for (part, expected) in [(1, &sample.part_1), (2, &sample.part_2)] {
+            if let Some(expected) = expected {
+                tests.push_str(&format!(
+                    "    assert_eq!(answer_part_{part}({constant}), {});\n",
+                    expected.literal()
+                ));
+            }
+        }
+        tests.push_str("}\n");
+    }
+    constants + &tests
+}
+
+/// A lowercase Rust identifier made from a sample name.
+fn identifier(name: &str) -> String {
+    let mut identifier = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            identifier.push(c.to_ascii_lowercase());
+        } else if !identifier.ends_with('_') {
+            identifier.push('_');
+        }
+    }
+    let identifier = identifier.trim_matches('_');
+    match identifier.chars().next() {
+        None => "sample".to_owned(),
+        Some(c) if c.is_ascii_digit() => format!("sample_{identifier}"),
+        Some(_) => identifier.to_owned(),
+    }
+}
+
+/// Inputs without the leading and trailing newlines that raw strings often pick up.
+fn trim_lines(input: &str) -> String {
+    input.trim_matches('\n').to_owned()
+}
+
+pub(crate) fn raw_string(content: &str) -> String {
+    let mut hashes = String::new();
+    while content.contains(&format!("\"{hashes}")) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{content}\"{hashes}")
+}
+
+/// Problems with a list of samples that would make for useless or clashing tests.
+fn check_samples(samples: &[NamedSample]) -> Result<(), String> {
+    if samples.is_empty() {
+        return Err("`samples` is empty".to_owned());
+    }
+    let mut names = HashSet::new();
+    for sample in samples {
+        if sample.part_1.is_none() && sample.part_2.is_none() {
+            return Err(format!(
+                "sample `{}` has neither `part_1` nor `part_2`",
+                sample.name
+            ));
+        }
+        if !names.insert(identifier(&sample.name)) {
+            return Err(format!("more than one sample is named `{}`", sample.name));
+        }
+    }
+    Ok(())
+}
+
+/// Why a generated snippet was rejected, located within the snippet.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ValidationError {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) message: String,
+}
+
+impl ValidationError {
+    fn at(span: proc_macro2::Span, message: String) -> Self {
+        let start = span.start();
+        Self {
+            line: start.line,
+            column: start.column + 1,
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum GenerationError {
+    Puzzle(std::io::Error),
+    FewShot(std::io::Error),
+    Llm(LlmError),
+    /// Every attempt was rejected; holds the reason for each one.
+    Rejected(Vec<String>),
+}
+
+impl std::fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GenerationError::Puzzle(err) => write!(f, "couldn't read the puzzle: {err}"),
+            GenerationError::FewShot(err) => {
+                write!(f, "couldn't load the few-shot examples: {err}")
+            }
+            GenerationError::Llm(err) => write!(f, "{err}"),
+            GenerationError::Rejected(reasons) => {
+                write!(f, "gave up after {} invalid attempt(s):", reasons.len())?;
+                for (attempt, reason) in reasons.iter().enumerate() {
+                    write!(f, "\n  attempt {}: {reason}", attempt + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<LlmError> for GenerationError {
+    fn from(err: LlmError) -> Self {
+        GenerationError::Llm(err)
+    }
+}
+
+/// Sample tests for a day; see [`generate_samples`].
+pub(crate) fn generate_sample_test_case(
+    backend: &dyn LlmBackend,
+    few_shot_dir: &Path,
+    puzzle_path: &Path,
+    attempts: usize,
+) -> Result<String, GenerationError> {
+    generate_samples(backend, few_shot_dir, puzzle_path, attempts).map(|samples| render(&samples))
+}
+
+/// A day's samples, taken from the examples in the puzzle text at `puzzle_path` when they can
+/// be found there.
+/// Otherwise `backend` is asked for them, prompted with the few-shot examples in
+/// `few_shot_dir` and re-prompted with the problem whenever the response doesn't produce
+/// valid tests, for at most `attempts` requests.
+pub(crate) fn generate_samples(
+    backend: &dyn LlmBackend,
+    few_shot_dir: &Path,
+    puzzle_path: &Path,
+    attempts: usize,
+) -> Result<Vec<NamedSample>, GenerationError> {
+    let puzzle = std::fs::read_to_string(puzzle_path).map_err(GenerationError::Puzzle)?;
+    if let Some(samples) = extract_samples(&puzzle) {
+        return Ok(samples.named());
+    }
+    let few_shots = load_few_shots(few_shot_dir).map_err(GenerationError::FewShot)?;
+    generate_validated(backend, &few_shots, &puzzle, attempts)
+}
+
+fn generate_validated(
+    backend: &dyn LlmBackend,
+    few_shots: &[FewShot],
+    puzzle: &str,
+    attempts: usize,
+) -> Result<Vec<NamedSample>, GenerationError> {
+    let mut messages = vec![Message::system(SYSTEM_PROMPT)];
+    for few_shot in few_shots {
+        messages.push(Message::example("example_user", &few_shot.puzzle));
+        messages.push(Message::example("example_assistant", &few_shot.response));
+    }
+    messages.push(Message::user(puzzle));
+
+    let mut reasons = vec![];
+    for _ in 0..attempts.max(1) {
+        let content = backend.complete(&messages)?;
+        let reason = match serde_json::from_str::<SamplesResponse>(&content) {
+            Ok(response) => match check_samples(&response.samples) {
+                Ok(()) => match validate(&render(&response.samples)) {
+                    Ok(()) => return Ok(response.samples),
+                    Err(err) => format!("the samples made invalid tests at {err}"),
+                },
+                Err(err) => err,
+            },
+            Err(err) => format!("the response wasn't a JSON object with `samples`: {err}"),
+        };
+        messages.push(Message::assistant(&content));
+        messages.push(Message::user(&format!(
+            "{reason}. Reply with corrected JSON only."
+        )));
+        reasons.push(reason);
+    }
+    Err(GenerationError::Rejected(reasons))
+}
+
+/// The result of [`merge_part_two`]: the updated day source and the tests added to it.
+#[derive(Debug)]
+pub(crate) struct Merged {
+    pub(crate) source: String,
+    pub(crate) added: Vec<String>,
+}
+
+/// Add a part-two test for each sample with a part-two answer to the `tests` module of a day's
+/// source, leaving everything already there alone. Samples whose input some existing test
+/// already passes to `answer_part_2` are skipped, and a constant already holding a sample's
+/// input is reused rather than duplicated.
+pub(crate) fn merge_part_two(
+    source: &str,
+    samples: &[NamedSample],
+) -> Result<Merged, ValidationError> {
+    let file = syn::parse_file(source).map_err(|e| ValidationError::at(e.span(), e.to_string()))?;
+    let Some((brace, items)) = file.items.iter().find_map(|item| match item {
+        Item::Mod(module) if module.ident == "tests" => module.content.as_ref(),
+        _ => None,
+    }) else {
+        return Err(ValidationError {
+            line: 1,
+            column: 1,
+            message: "there is no `mod tests` to add tests to".to_owned(),
+        });
+    };
+
+    let mut existing = Existing::default();
+    for item in items {
+        match item {
+            Item::Const(item) => {
+                if let Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }) = item.expr.as_ref()
+                {
+                    existing
+                        .constants
+                        .insert(trim_lines(&value.value()), item.ident.to_string());
+                }
+                existing.names.insert(item.ident.to_string());
+            }
+            Item::Fn(item) => {
+                existing.names.insert(item.sig.ident.to_string());
+            }
+            _ => {}
+        }
+    }
+    for item in items {
+        existing.visit_item(item);
+    }
+
+    let mut new_samples: Vec<NamedSample> = vec![];
+    for sample in samples {
+        if sample.part_2.is_none()
+            || existing.tested.contains(&trim_lines(&sample.input))
+            || new_samples
+                .iter()
+                .any(|new| trim_lines(&new.input) == trim_lines(&sample.input))
+        {
+            continue;
+        }
+        let mut name = identifier(&sample.name);
+        if !name.starts_with("part_2") {
+            name = format!("part_2_{name}");
+        }
+        let taken = |name: &str| {
+            existing.names.contains(&format!("test_{name}"))
+                || existing
+                    .names
+                    .contains(&format!("SAMPLE_{}", name.to_uppercase()))
+        };
+        let mut unique = name.clone();
+        for n in 2.. {
+            if !taken(&unique) {
+                break;
+            }
+            unique = format!("{name}_{n}");
+        }
+        new_samples.push(NamedSample {
+            name: unique,
+            input: sample.input.clone(),
+            part_1: None,
+            part_2: sample.part_2.clone(),
+        });
+    }
+    if new_samples.is_empty() {
+        return Ok(Merged {
+            source: source.to_owned(),
+            added: vec![],
+        });
+    }
+
+    // insert just before the module's closing brace; `cargo fmt` takes care of indentation
+    let close = brace.span.close().start();
+    let offset = source
+        .split_inclusive('\n')
+        .take(close.line - 1)
+        .map(str::len)
+        .sum::<usize>()
+        + source
+            .lines()
+            .nth(close.line - 1)
+            .and_then(|line| line.char_indices().nth(close.column))
+            .map_or(0, |(index, _)| index);
+    let mut merged = source[..offset].trim_end().to_owned();
+    merged.push('\n');
+    merged.push_str(&render_reusing(&new_samples, &existing.constants));
+    merged.push_str(&source[offset..]);
+    Ok(Merged {
+        source: merged,
+        added: new_samples
+            .iter()
+            .map(|sample| format!("test_{}", sample.name))
+            .collect(),
+    })
+}
+
+/// What the `tests` module of a day already has.
+#[derive(Default)]
+struct Existing {
+    /// Every constant and function name.
+    names: HashSet<String>,
+    /// String constants, keyed by their value without surrounding blank lines.
+    constants: HashMap<String, String>,
+    /// Inputs passed to `answer_part_2`, either as a string literal or a constant.
+    tested: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for Existing {
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        let is_part_two =
+            matches!(call.func.as_ref(), Expr::Path(path) if path.path.is_ident("answer_part_2"));
+        match call.args.first() {
+            Some(Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(value),
+                ..
+            })) if is_part_two => {
+                self.tested.insert(trim_lines(&value.value()));
+            }
+            Some(Expr::Path(path)) if is_part_two => {
+                let constant = path.path.get_ident().map(|ident| ident.to_string());
+                if let Some((input, _)) = self
+                    .constants
+                    .iter()
+                    .find(|(_, name)| Some(*name) == constant.as_ref())
+                {
+                    self.tested.insert(input.clone());
+                }
+            }
+            _ => {}
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        if let Ok(args) = Punctuated::<Expr, Token![,]>::parse_terminated.parse2(mac.tokens.clone())
+        {
+            args.iter().for_each(|arg| self.visit_expr(arg));
+        }
+    }
+}
+
+/// Check that a snippet parses as Rust items and only defines constants and test functions
+/// whose bodies call `answer_part_1`/`answer_part_2` with constants or local bindings.
+pub(crate) fn validate(code: &str) -> Result<(), ValidationError> {
+    let file = syn::parse_file(code).map_err(|e| ValidationError::at(e.span(), e.to_string()))?;
+
+    let mut checker = Checker::default();
+    for item in &file.items {
+        match item {
+            Item::Const(item) => {
+                checker.names.insert(item.ident.to_string());
+            }
+            Item::Fn(_) => {}
+            other => {
+                return Err(ValidationError::at(
+                    other.span(),
+                    "only constants and test functions are allowed".to_owned(),
+                ))
+            }
+        }
+    }
+    for item in &file.items {
+        checker.visit_item(item);
+    }
+    checker.error.map_or(Ok(()), Err)
+}
+
+#[derive(Default)]
+struct Checker {
+    /// Constants and local bindings seen so far.
+    names: HashSet<String>,
+    error: Option<ValidationError>,
+}
+
+impl Checker {
+    fn reject(&mut self, span: proc_macro2::Span, message: String) {
+        if self.error.is_none() {
+            self.error = Some(ValidationError::at(span, message));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Checker {
+    fn visit_expr_path(&mut self, expr: &'ast ExprPath) {
+        match expr.path.get_ident() {
+            Some(ident)
+                if ALLOWED_FUNCTIONS.contains(&ident.to_string().as_str())
+                    || self.names.contains(&ident.to_string()) => {}
+            Some(ident) => self.reject(
+                ident.span(),
+                format!("`{ident}` is not `answer_part_1`, `answer_part_2` or a constant"),
+            ),
+            None => self.reject(
+                expr.span(),
+                "only `answer_part_1`, `answer_part_2` and constants may be referenced".to_owned(),
+            ),
+        }
+    }
+
+    fn visit_pat_ident(&mut self, pat: &'ast PatIdent) {
+        self.names.insert(pat.ident.to_string());
+        syn::visit::visit_pat_ident(self, pat);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro) {
+        let name = mac
+            .path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .unwrap_or_default();
+        if !ALLOWED_MACROS.contains(&name.as_str()) {
+            self.reject(
+                mac.path.span(),
+                "only `assert!`, `assert_eq!` and `assert_ne!` may be used".to_owned(),
+            );
+            return;
+        }
+        match Punctuated::<Expr, Token![,]>::parse_terminated.parse2(mac.tokens.clone()) {
+            Ok(args) => args.iter().for_each(|arg| self.visit_expr(arg)),
+            Err(err) => self.reject(err.span(), err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        llm::{FixtureBackend, LlmBackend, LlmError, Message},
+        sample_tests::{
+            generate_validated, load_few_shots, merge_part_two, raw_string, render, validate,
+            Expected, GenerationError, NamedSample,
+        },
+        solution::Registry,
+    };
+    use std::{path::Path, sync::Mutex};
+
+    #[test]
+    fn test_render_samples() {
+        let code = render(&[
+            NamedSample {
+                name: "Two steps".to_owned(),
+                input: "RL\n\nAAA = (BBB, CCC)".to_owned(),
+                part_1: Some(Expected::Integer(2)),
+                part_2: None,
+            },
+            NamedSample {
+                name: "ghosts".to_owned(),
+                input: "say \"11A\"".to_owned(),
+                part_1: None,
+                part_2: Some(Expected::Text("6".to_owned())),
+            },
+        ]);
+
+        assert_eq!(
+            code,
+            "const SAMPLE_TWO_STEPS: &str = r\"RL\n\nAAA = (BBB, CCC)\";\nconst SAMPLE_GHOSTS: &str = r#\"say \"11A\"\"#;\n\n#[test]\nfn test_two_steps() {\n    assert_eq!(answer_part_1(SAMPLE_TWO_STEPS), 2);\n}\n\n#[test]\nfn test_ghosts() {\n    assert_eq!(answer_part_2(SAMPLE_GHOSTS), 6);\n}\n"
+        );
+        assert_eq!(validate(&code), Ok(()));
+    }
+
+    #[test]
+    fn test_raw_string() {
+        assert_eq!(raw_string("a\nb"), "r\"a\nb\"");
+        assert_eq!(raw_string("say \"hi\""), "r#\"say \"hi\"\"#");
+    }
+
+    /// The shipped few-shot examples should be right, or they'd teach the model wrong answers.
+    #[test]
+    fn test_few_shot_library() {
+        let few_shots = load_few_shots(Path::new("few_shot")).unwrap();
+        assert!(few_shots.len() >= 2);
+
+        let registry = Registry::new();
+        for path in std::fs::read_dir("few_shot").unwrap() {
+            let file: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(path.unwrap().path()).unwrap())
+                    .unwrap();
+            let day: usize = file["puzzle"]
+                .as_str()
+                .unwrap()
+                .trim_start_matches("puzzles/2023/day")
+                .trim_end_matches(".md")
+                .parse()
+                .unwrap();
+            let solution = registry.get(2023, day).unwrap();
+            let samples: Vec<NamedSample> =
+                serde_json::from_value(file["samples"].clone()).unwrap();
+            for sample in samples {
+                if let Some(expected) = sample.part_1 {
+                    assert_eq!(
+                        solution.part_one(&sample.input).unwrap().to_string(),
+                        expected.literal()
+                    );
+                }
+                if let Some(expected) = sample.part_2 {
+                    assert_eq!(
+                        solution.part_two(&sample.input).unwrap().to_string(),
+                        expected.literal()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_syntax_errors() {
+        let err = validate("#[test]\nfn broken() {\n    assert_eq!(answer_part_1(\"x\"), 1)\n")
+            .unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn test_rejects_unknown_references() {
+        let err =
+            validate("#[test]\nfn t() {\n    assert_eq!(solve(SAMPLE), 1);\n}\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 16));
+        assert!(err.message.contains("`solve`"));
+
+        let err = validate("use std::fs;\n").unwrap_err();
+        assert!(err.message.contains("only constants and test functions"));
+
+        let err =
+            validate("fn t() {\n    println!(\"{}\", answer_part_1(\"\"));\n}\n").unwrap_err();
+        assert!(err.message.contains("assert!"));
+
+        let err = validate("fn t() { assert!(std::fs::read(\"x\").is_ok()); }").unwrap_err();
+        assert!(err.message.contains("may be referenced"));
+    }
+
+    #[test]
+    fn test_allows_locals() {
+        let code = "const A: &str = \"1\";\n#[test]\nfn t() {\n    let input = A.trim();\n    assert_eq!(answer_part_2(input), 2, \"part {}\", 2);\n}\n";
+        assert_eq!(validate(code), Ok(()));
+    }
+
+    struct Recording {
+        responses: FixtureBackend,
+        prompts: Mutex<Vec<Vec<Message>>>,
+    }
+
+    impl LlmBackend for Recording {
+        fn complete(&self, messages: &[Message]) -> Result<String, LlmError> {
+            self.prompts.lock().unwrap().push(messages.to_vec());
+            self.responses.complete(messages)
+        }
+    }
+
+    fn recording(responses: &[&str]) -> Recording {
+        Recording {
+            responses: FixtureBackend::new(responses.iter().map(|r| r.to_string()).collect()),
+            prompts: Mutex::new(vec![]),
+        }
+    }
+
+    #[test]
+    fn test_retries_with_error() {
+        let backend = recording(&[
+            "not json",
+            r#"{"samples": [{"name": "a", "input": "x"}]}"#,
+            r#"{"samples": [{"name": "a", "input": "x", "part_1": 1}, {"name": "A", "input": "y", "part_2": 2}]}"#,
+            r#"{"samples": [{"name": "a", "input": "x", "part_1": 1}]}"#,
+        ]);
+        let code = render(&generate_validated(&backend, &[], "puzzle", 4).unwrap());
+
+        assert!(code.contains("assert_eq!(answer_part_1(SAMPLE_A), 1);"));
+        let prompts = backend.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 4);
+        assert!(prompts[1]
+            .last()
+            .unwrap()
+            .content
+            .contains("wasn't a JSON object"));
+        assert!(prompts[2]
+            .last()
+            .unwrap()
+            .content
+            .contains("sample `a` has neither `part_1` nor `part_2`"));
+        assert!(prompts[3]
+            .last()
+            .unwrap()
+            .content
+            .contains("more than one sample is named `A`"));
+    }
+
+    #[test]
+    fn test_gives_up() {
+        let backend = recording(&["{}"]);
+        match generate_validated(&backend, &[], "puzzle", 2) {
+            Err(GenerationError::Rejected(reasons)) => assert_eq!(reasons.len(), 2),
+            _ => panic!("expected the generation to be rejected"),
+        }
+    }
+
+    const DAY_SOURCE: &str = r#"fn answer_part_1(input: &str) -> usize {
+    input.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{answer_part_1, answer_part_2};
+
+    const SAMPLE_EXAMPLE: &str = r"1 2
+3 4";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(answer_part_1(SAMPLE_EXAMPLE), 10);
+    }
+
+    // written by hand
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(answer_part_2("edge case"), 0);
+    }
+}
+"#;
+
+    fn part_two_sample(name: &str, input: &str, answer: i64) -> NamedSample {
+        NamedSample {
+            name: name.to_owned(),
+            input: input.to_owned(),
+            part_1: Some(Expected::Integer(10)),
+            part_2: Some(Expected::Integer(answer)),
+        }
+    }
+
+    #[test]
+    fn test_merge_part_two() {
+        let samples = [
+            part_two_sample("example", "1 2\n3 4", 24),
+            part_two_sample("Larger", "5 6\n", 30),
+            part_two_sample("larger again", "\n5 6", 30),
+            part_two_sample("hand written", "edge case", 0),
+        ];
+        let merged = merge_part_two(DAY_SOURCE, &samples).unwrap();
+
+        assert_eq!(
+            merged.added,
+            ["test_part_2_example_2", "test_part_2_larger"]
+        );
+        assert!(merged
+            .source
+            .starts_with(&DAY_SOURCE[..DAY_SOURCE.rfind("    }\n}").unwrap() + "    }\n".len()]));
+        assert!(merged.source.ends_with(
+            "const SAMPLE_PART_2_LARGER: &str = r\"5 6\n\";\n\n#[test]\nfn test_part_2_example_2() {\n    assert_eq!(answer_part_2(SAMPLE_EXAMPLE), 24);\n}\n\n#[test]\nfn test_part_2_larger() {\n    assert_eq!(answer_part_2(SAMPLE_PART_2_LARGER), 30);\n}\n}\n"
+        ));
+
+        // running it again finds nothing new
+        let again = merge_part_two(&merged.source, &samples).unwrap();
+        assert!(again.added.is_empty());
+        assert_eq!(again.source, merged.source);
+    }
+
+    #[test]
+    fn test_merge_needs_tests_module() {
+        let err = merge_part_two("fn main() {}", &[]).unwrap_err();
+        assert_eq!(err.message, "there is no `mod tests` to add tests to");
+        assert_eq!(merge_part_two("fn main() {", &[]).unwrap_err().line, 1);
+    }
+}
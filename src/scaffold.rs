@@ -0,0 +1,229 @@
+use handlebars::Handlebars;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+pub(crate) const DEFAULT_TEMPLATE_DIR: &str = "templates";
+
+const DAY_TEMPLATE: &str = "day.rs.hbs";
+const YEAR_MOD_TEMPLATE: &str = "year_mod.rs.hbs";
+
+/// Templates used to generate new solution modules. Each one is read from the template
+/// directory when present there, so a copy can be edited without rebuilding, and falls
+/// back to the version in the repo's `templates/` directory otherwise.
+pub(crate) struct Templates {
+    handlebars: Handlebars<'static>,
+}
+
+impl Templates {
+    pub(crate) fn load(dir: &Path) -> std::io::Result<Self> {
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(true);
+        handlebars.register_escape_fn(handlebars::no_escape);
+        for (name, builtin) in [
+            (DAY_TEMPLATE, include_str!("../templates/day.rs.hbs")),
+            (
+                YEAR_MOD_TEMPLATE,
+                include_str!("../templates/year_mod.rs.hbs"),
+            ),
+        ] {
+            let content = match std::fs::read_to_string(dir.join(name)) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => builtin.to_owned(),
+                Err(e) => return Err(e),
+            };
+            handlebars
+                .register_template_string(name, content)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(Self { handlebars })
+    }
+
+    pub(crate) fn render_day(
+        &self,
+        year: u16,
+        day: usize,
+        title: &str,
+        test_case: &str,
+    ) -> Result<String, handlebars::RenderError> {
+        self.handlebars.render(
+            DAY_TEMPLATE,
+            &json!({
+                "year": year,
+                "day": day,
+                "padded_day": format!("{day:02}"),
+                "title_literal": format!("{title:?}"),
+                "test_case": test_case,
+            }),
+        )
+    }
+
+    pub(crate) fn render_year_mod(&self, year: u16) -> Result<String, handlebars::RenderError> {
+        self.handlebars
+            .render(YEAR_MOD_TEMPLATE, &json!({ "year": year }))
+    }
+}
+
+pub(crate) fn year_dir(year: u16) -> PathBuf {
+    PathBuf::from(format!("src/aoc{year}"))
+}
+
+pub(crate) fn day_path(year: u16, day: usize) -> PathBuf {
+    year_dir(year).join(format!("day{day:02}.rs"))
+}
+
+/// Add `dayNN`'s module declaration and registration to a year's `mod.rs`.
+pub(crate) fn register_day(mod_rs: &str, day: usize) -> String {
+    let with_mod = insert_line(
+        mod_rs,
+        &format!("mod day{day:02};"),
+        |line| line.starts_with("mod day"),
+        None,
+    );
+    insert_line(
+        &with_mod,
+        &format!("    registry.register(day{day:02}::Day{day:02});"),
+        |line| line.trim_start().starts_with("registry.register(day"),
+        Some("pub(crate) fn register(registry: &mut Registry) {"),
+    )
+}
+
+/// Declare a new year module in `main.rs`.
+pub(crate) fn declare_year(main_rs: &str, year: u16) -> String {
+    insert_line(
+        main_rs,
+        &format!("mod aoc{year};"),
+        |line| line.starts_with("mod "),
+        None,
+    )
+}
+
+/// Call a new year's `register` from `Registry::new` in `solution.rs`.
+pub(crate) fn register_year(solution_rs: &str, year: u16) -> String {
+    insert_line(
+        solution_rs,
+        &format!("        crate::aoc{year}::register(&mut registry);"),
+        |line| line.trim_start().starts_with("crate::aoc"),
+        Some("let mut registry = Self::empty();"),
+    )
+}
+
+/// Insert `new_line` into the run of lines matching `group`, keeping the run sorted. When
+/// nothing matches yet it goes after the line `after`, or at the top followed by a blank line.
+/// Does nothing if the line is already present.
+fn insert_line(
+    source: &str,
+    new_line: &str,
+    group: impl Fn(&str) -> bool,
+    after: Option<&str>,
+) -> String {
+    let mut lines: Vec<&str> = source.lines().collect();
+    if lines.iter().any(|line| line.trim() == new_line.trim()) {
+        return source.to_owned();
+    }
+
+    let grouped: Vec<usize> = (0..lines.len()).filter(|i| group(lines[*i])).collect();
+    match (grouped.first(), after) {
+        (Some(_), _) => {
+            let position = grouped
+                .iter()
+                .copied()
+                .find(|i| lines[*i] > new_line)
+                .unwrap_or(grouped[grouped.len() - 1] + 1);
+            lines.insert(position, new_line);
+        }
+        (None, Some(after)) => {
+            let position = lines
+                .iter()
+                .position(|line| line.trim() == after)
+                .map_or(lines.len(), |i| i + 1);
+            lines.insert(position, new_line);
+        }
+        (None, None) => {
+            lines.insert(0, new_line);
+            lines.insert(1, "");
+        }
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scaffold::{declare_year, register_day, register_year, Templates};
+    use std::path::Path;
+
+    #[test]
+    fn test_render_day() {
+        let templates = Templates::load(Path::new("templates")).unwrap();
+        let rendered = templates
+            .render_day(2024, 3, "Mull It \"Over\"", "// sample tests")
+            .unwrap();
+
+        assert!(rendered.contains("pub(crate) struct Day03;"));
+        assert!(rendered.contains("\"Mull It \\\"Over\\\"\""));
+        assert!(rendered.contains("use crate::aoc2024::day03::{answer_part_1, answer_part_2};"));
+        assert!(rendered.contains("input::load(2024, 3, &InputSource::default())"));
+        assert!(rendered.contains("// sample tests"));
+    }
+
+    #[test]
+    fn test_template_override() {
+        let dir = std::env::temp_dir().join("aoc-scaffold-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("day.rs.hbs"),
+            "// day {{padded_day}} of {{year}}\n",
+        )
+        .unwrap();
+        let templates = Templates::load(&dir).unwrap();
+
+        assert_eq!(
+            templates.render_day(2024, 7, "Bridge Repair", "").unwrap(),
+            "// day 07 of 2024\n"
+        );
+        // templates missing from the directory use the built-in version
+        assert!(templates
+            .render_year_mod(2024)
+            .unwrap()
+            .contains("pub(crate) fn register"));
+    }
+
+    #[test]
+    fn test_register_day() {
+        let mod_rs = "mod day01;\nmod day03;\n\nuse crate::solution::Registry;\n\npub(crate) fn register(registry: &mut Registry) {\n    registry.register(day01::Day01);\n    registry.register(day03::Day03);\n}\n";
+        let updated = register_day(mod_rs, 2);
+
+        assert_eq!(
+            updated,
+            "mod day01;\nmod day02;\nmod day03;\n\nuse crate::solution::Registry;\n\npub(crate) fn register(registry: &mut Registry) {\n    registry.register(day01::Day01);\n    registry.register(day02::Day02);\n    registry.register(day03::Day03);\n}\n"
+        );
+        assert_eq!(register_day(&updated, 2), updated);
+    }
+
+    #[test]
+    fn test_register_day_in_new_year() {
+        let templates = Templates::load(Path::new("templates")).unwrap();
+        let mod_rs = templates.render_year_mod(2024).unwrap();
+
+        assert_eq!(
+            register_day(&mod_rs, 1),
+            "mod day01;\n\nuse crate::solution::Registry;\n\npub(crate) fn register(registry: &mut Registry) {\n    registry.register(day01::Day01);\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_register_year() {
+        let main_rs = include_str!("main.rs");
+        let declared = declare_year(main_rs, 2024);
+        assert!(declared.contains("mod aoc2023;\nmod aoc2024;\nmod bench;"));
+
+        let solution_rs = include_str!("solution.rs");
+        let registered = register_year(solution_rs, 2024);
+        assert!(registered.contains(
+            "crate::aoc2023::register(&mut registry);\n        crate::aoc2024::register(&mut registry);"
+        ));
+        assert_eq!(register_year(&registered, 2023), registered);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{answer::Answer, parse::ParseError};
+use std::collections::BTreeMap;
+
+/// A single day's puzzle solution. Every `aocYYYY::dayNN` module provides one of these
+/// and registers it with the [`Registry`] through its year's `register` function.
+pub(crate) trait Solution: Sync {
+    fn year(&self) -> u16;
+    fn day(&self) -> usize;
+    fn title(&self) -> &'static str;
+    fn part_one(&self, input: &str) -> Result<Answer, ParseError>;
+    fn part_two(&self, input: &str) -> Result<Answer, ParseError>;
+}
+
+/// All known solutions, keyed by `(year, day)`.
+pub(crate) struct Registry {
+    solutions: BTreeMap<(u16, usize), Box<dyn Solution>>,
+}
+
+impl Registry {
+    /// Build a registry containing every solution compiled into the binary.
+    pub(crate) fn new() -> Self {
+        let mut registry = Self::empty();
+        crate::aoc2023::register(&mut registry);
+        registry
+    }
+
+    fn empty() -> Self {
+        Self {
+            solutions: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn register(&mut self, solution: impl Solution + 'static) {
+        let key = (solution.year(), solution.day());
+        if self.solutions.insert(key, Box::new(solution)).is_some() {
+            panic!("day {} of {} registered twice", key.1, key.0);
+        }
+    }
+
+    pub(crate) fn get(&self, year: u16, day: usize) -> Option<&dyn Solution> {
+        self.solutions.get(&(year, day)).map(|s| s.as_ref())
+    }
+
+    /// Every registered solution for `year`, ordered by day.
+    pub(crate) fn for_year(&self, year: u16) -> impl Iterator<Item = &dyn Solution> {
+        self.solutions
+            .range((year, 0)..=(year, usize::MAX))
+            .map(|(_, s)| s.as_ref())
+    }
+
+    /// Days between 1 and 25 that have no registered solution for `year`.
+    pub(crate) fn missing_days(&self, year: u16) -> Vec<usize> {
+        (1..=25)
+            .filter(|day| !self.solutions.contains_key(&(year, *day)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answer::Answer,
+        parse::ParseError,
+        solution::{Registry, Solution},
+    };
+
+    struct Fake(u16, usize);
+
+    impl Solution for Fake {
+        fn year(&self) -> u16 {
+            self.0
+        }
+        fn day(&self) -> usize {
+            self.1
+        }
+        fn title(&self) -> &'static str {
+            "Fake"
+        }
+        fn part_one(&self, _input: &str) -> Result<Answer, ParseError> {
+            Ok(1usize.into())
+        }
+        fn part_two(&self, _input: &str) -> Result<Answer, ParseError> {
+            Ok(2usize.into())
+        }
+    }
+
+    #[test]
+    fn test_lookup_by_year_and_day() {
+        let mut registry = Registry::empty();
+        registry.register(Fake(2023, 2));
+        registry.register(Fake(2023, 1));
+        registry.register(Fake(2024, 1));
+
+        assert_eq!(registry.get(2023, 2).map(|s| s.day()), Some(2));
+        assert!(registry.get(2022, 1).is_none());
+        assert_eq!(
+            registry.for_year(2023).map(|s| s.day()).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(registry.missing_days(2024).len(), 24);
+    }
+
+    #[test]
+    #[should_panic(expected = "registered twice")]
+    fn test_duplicate_registration() {
+        let mut registry = Registry::empty();
+        registry.register(Fake(2023, 1));
+        registry.register(Fake(2023, 1));
+    }
+
+    #[test]
+    fn test_all_2023_days_registered() {
+        let registry = Registry::new();
+        assert_eq!(registry.for_year(2023).count(), 16);
+    }
+}
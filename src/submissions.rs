@@ -0,0 +1,259 @@
+use crate::client::{SubmissionResult, Verdict};
+use num::BigInt;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// One answer sent to the site and what it said about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Attempt {
+    pub(crate) day: usize,
+    pub(crate) part: usize,
+    pub(crate) answer: String,
+    pub(crate) verdict: Verdict,
+    /// Unix timestamp in seconds.
+    pub(crate) submitted_at: i64,
+    /// Lockout the site asked for after this attempt, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) wait: Option<u64>,
+}
+
+impl Attempt {
+    pub(crate) fn new(
+        day: usize,
+        part: usize,
+        answer: &str,
+        result: &SubmissionResult,
+        submitted_at: i64,
+    ) -> Self {
+        Self {
+            day,
+            part,
+            answer: answer.to_owned(),
+            verdict: result.verdict,
+            submitted_at,
+            wait: result.wait.map(|w| w.as_secs()),
+        }
+    }
+}
+
+/// Why an answer shouldn't be submitted right now.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Refusal {
+    AlreadySolved { answer: String },
+    AlreadyRejected { verdict: Verdict },
+    AtLeastTooHigh { bound: String },
+    AtMostTooLow { bound: String },
+}
+
+impl std::fmt::Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Refusal::AlreadySolved { answer } => {
+                write!(f, "this part was already solved with {answer}")
+            }
+            Refusal::AlreadyRejected { verdict } => {
+                write!(
+                    f,
+                    "this answer was already submitted and rejected ({verdict:?})"
+                )
+            }
+            Refusal::AtLeastTooHigh { bound } => {
+                write!(f, "{bound} was already too high, this answer is not lower")
+            }
+            Refusal::AtMostTooLow { bound } => {
+                write!(f, "{bound} was already too low, this answer is not higher")
+            }
+        }
+    }
+}
+
+/// Every submission made for a year, stored as `answers/<year>-submissions.json`.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct SubmissionLog {
+    attempts: Vec<Attempt>,
+}
+
+pub(crate) fn log_path(dir: &Path, year: u16) -> PathBuf {
+    dir.join(format!("{year}-submissions.json"))
+}
+
+impl SubmissionLog {
+    pub(crate) fn load(dir: &Path, year: u16) -> std::io::Result<Self> {
+        match std::fs::read_to_string(log_path(dir, year)) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) fn save(&self, dir: &Path, year: u16) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(log_path(dir, year), serde_json::to_string_pretty(self)?)
+    }
+
+    pub(crate) fn record(&mut self, attempt: Attempt) {
+        self.attempts.push(attempt);
+    }
+
+    fn attempts_for(&self, day: usize, part: usize) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| a.day == day && a.part == part)
+    }
+
+    /// Time left on the most recent lockout for this day, across both parts.
+    pub(crate) fn cooldown(&self, day: usize, now: i64) -> Option<Duration> {
+        self.attempts
+            .iter()
+            .filter(|a| a.day == day)
+            .filter_map(|a| a.wait.map(|w| a.submitted_at + w as i64 - now))
+            .max()
+            .filter(|remaining| *remaining > 0)
+            .map(|remaining| Duration::from_secs(remaining as u64))
+    }
+
+    /// Reasons not to submit `answer`, judged only from answers that were rejected or accepted
+    /// before. Cooldowns are checked separately so callers can choose to wait them out.
+    pub(crate) fn check(&self, day: usize, part: usize, answer: &str) -> Option<Refusal> {
+        if let Some(solved) = self
+            .attempts_for(day, part)
+            .find(|a| a.verdict == Verdict::Correct)
+        {
+            return Some(Refusal::AlreadySolved {
+                answer: solved.answer.clone(),
+            });
+        }
+
+        if let Some(rejected) = self.attempts_for(day, part).find(|a| {
+            a.answer == answer
+                && matches!(
+                    a.verdict,
+                    Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow
+                )
+        }) {
+            return Some(Refusal::AlreadyRejected {
+                verdict: rejected.verdict,
+            });
+        }
+
+        let value = BigInt::from_str(answer).ok()?;
+        let bound = |verdict| {
+            self.attempts_for(day, part)
+                .filter(move |a| a.verdict == verdict)
+                .filter_map(|a| BigInt::from_str(&a.answer).ok())
+        };
+        if let Some(high) = bound(Verdict::TooHigh).min() {
+            if value >= high {
+                return Some(Refusal::AtLeastTooHigh {
+                    bound: high.to_string(),
+                });
+            }
+        }
+        if let Some(low) = bound(Verdict::TooLow).max() {
+            if value <= low {
+                return Some(Refusal::AtMostTooLow {
+                    bound: low.to_string(),
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        client::Verdict,
+        submissions::{Attempt, Refusal, SubmissionLog},
+    };
+    use std::time::Duration;
+
+    fn attempt(part: usize, answer: &str, verdict: Verdict, wait: Option<u64>) -> Attempt {
+        Attempt {
+            day: 12,
+            part,
+            answer: answer.to_owned(),
+            verdict,
+            submitted_at: 1_000,
+            wait,
+        }
+    }
+
+    #[test]
+    fn test_refuses_known_bad_answers() {
+        let mut log = SubmissionLog::default();
+        log.record(attempt(2, "3497545717240", Verdict::TooLow, Some(60)));
+        log.record(attempt(2, "9000000000000", Verdict::TooHigh, Some(60)));
+        log.record(attempt(2, "abc", Verdict::Wrong, Some(60)));
+
+        assert_eq!(
+            log.check(12, 2, "abc"),
+            Some(Refusal::AlreadyRejected {
+                verdict: Verdict::Wrong
+            })
+        );
+        assert_eq!(
+            log.check(12, 2, "3497545717240"),
+            Some(Refusal::AlreadyRejected {
+                verdict: Verdict::TooLow
+            })
+        );
+        assert_eq!(
+            log.check(12, 2, "100"),
+            Some(Refusal::AtMostTooLow {
+                bound: "3497545717240".to_owned()
+            })
+        );
+        assert_eq!(
+            log.check(12, 2, "9000000000001"),
+            Some(Refusal::AtLeastTooHigh {
+                bound: "9000000000000".to_owned()
+            })
+        );
+        assert_eq!(log.check(12, 2, "4000000000000"), None);
+        // bounds are per part
+        assert_eq!(log.check(12, 1, "100"), None);
+    }
+
+    #[test]
+    fn test_refuses_after_solved() {
+        let mut log = SubmissionLog::default();
+        log.record(attempt(1, "7195", Verdict::Correct, None));
+
+        assert_eq!(
+            log.check(12, 1, "7196"),
+            Some(Refusal::AlreadySolved {
+                answer: "7195".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let mut log = SubmissionLog::default();
+        log.record(attempt(1, "1", Verdict::Wrong, Some(60)));
+
+        assert_eq!(log.cooldown(12, 1_030), Some(Duration::from_secs(30)));
+        assert_eq!(log.cooldown(12, 1_060), None);
+        assert_eq!(log.cooldown(11, 1_030), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut log = SubmissionLog::default();
+        log.record(attempt(1, "1", Verdict::TooLow, Some(60)));
+        let dir = std::env::temp_dir().join("aoc-submissions-test");
+        log.save(&dir, 2023).unwrap();
+
+        assert_eq!(SubmissionLog::load(&dir, 2023).unwrap(), log);
+        assert_eq!(
+            SubmissionLog::load(&dir, 1999).unwrap(),
+            SubmissionLog::default()
+        );
+    }
+}